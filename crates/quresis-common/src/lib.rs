@@ -0,0 +1,396 @@
+#![no_std]
+
+// ============================================================================
+// Quresis Common — Shared Account Layouts
+// ============================================================================
+//
+// Single source of truth for the byte layout of accounts that more than one
+// program reads. `quresis` owns `QuantumIdentity` as a zero-copy account;
+// `quresis-hook` reads it raw (no Anchor types) to keep `execute` cheap. Both programs
+// compile against the header below, and the layout assertions in this crate
+// and in `quresis` fail the build if either side drifts.
+//
+// No dependencies (not even `anchor-lang`), so it builds for SBF and host alike.
+
+use core::mem::{align_of, offset_of, size_of};
+
+// ============================================================================
+// QUANTUM IDENTITY
+// ============================================================================
+
+/// Anchor account discriminator: SHA256("account:QuantumIdentity")[0..8]
+pub const QUANTUM_IDENTITY_DISCRIMINATOR: [u8; 8] = [22, 56, 98, 16, 99, 95, 244, 76];
+
+/// Layout version of `QuantumIdentityHeader`, stored at `VERSION_OFFSET`.
+/// Bump it for any layout change and teach `quresis::migrate_identity` the
+/// conversion from the previous version.
+pub const QUANTUM_IDENTITY_VERSION: u8 = 1;
+
+/// The original Borsh layout (bump right after the authority, key stored as a
+/// `Vec<u8>` after an 88-byte body). It has no version field, so it is told
+/// apart by its shape instead: the Vec's u32 length prefix at
+/// `BORSH_KEY_LEN_OFFSET` accounts for exactly the rest of the account (see
+/// `is_borsh_identity`).
+pub const QUANTUM_IDENTITY_VERSION_BORSH: u8 = 0;
+
+/// Offset of the key's u32 length prefix in the Borsh layout
+pub const BORSH_KEY_LEN_OFFSET: usize = 92;
+/// Bytes before the key in the Borsh layout, discriminator included
+pub const BORSH_HEADER_LEN: usize = BORSH_KEY_LEN_OFFSET + 4;
+
+/// Fixed-size header of a `QuantumIdentity` account, discriminator included.
+/// Mirrors the `repr(C)` zero-copy struct in `quresis` (which has no padding)
+/// with every multi-byte field as a little-endian byte array, so this view has
+/// alignment 1 and can be cast from account data in place.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuantumIdentityHeader {
+    pub discriminator: [u8; 8],
+    pub authority: [u8; 32],
+    pub sequence: [u8; 8],
+    pub last_active_slot: [u8; 8],
+    pub created_at: [u8; 8],
+    pub threshold_amount: [u8; 8],
+    pub current_window_start: [u8; 8],
+    pub current_window_amount: [u8; 8],
+    pub key_version: [u8; 2],
+    pub key_written_len: [u8; 2],
+    pub key_len: [u8; 2],
+    pub bump: u8,
+    pub is_frozen: u8,
+    pub is_sealed: u8,
+    pub version: u8,
+    pub is_revoked: u8,
+    pub is_forwarded: u8,
+    pub mint_policy_count: [u8; 2],
+    pub reserved: [u8; 2],
+}
+
+/// Bytes covered by `QuantumIdentityHeader`; the `key_len`-byte public key
+/// starts here
+pub const QUANTUM_IDENTITY_HEADER_LEN: usize = size_of::<QuantumIdentityHeader>();
+
+pub const AUTHORITY_OFFSET: usize = offset_of!(QuantumIdentityHeader, authority);
+pub const SEQUENCE_OFFSET: usize = offset_of!(QuantumIdentityHeader, sequence);
+pub const LAST_ACTIVE_SLOT_OFFSET: usize = offset_of!(QuantumIdentityHeader, last_active_slot);
+pub const CREATED_AT_OFFSET: usize = offset_of!(QuantumIdentityHeader, created_at);
+pub const THRESHOLD_OFFSET: usize = offset_of!(QuantumIdentityHeader, threshold_amount);
+pub const WINDOW_START_OFFSET: usize = offset_of!(QuantumIdentityHeader, current_window_start);
+pub const WINDOW_AMOUNT_OFFSET: usize = offset_of!(QuantumIdentityHeader, current_window_amount);
+pub const KEY_VERSION_OFFSET: usize = offset_of!(QuantumIdentityHeader, key_version);
+pub const KEY_WRITTEN_LEN_OFFSET: usize = offset_of!(QuantumIdentityHeader, key_written_len);
+pub const KEY_LEN_OFFSET: usize = offset_of!(QuantumIdentityHeader, key_len);
+pub const BUMP_OFFSET: usize = offset_of!(QuantumIdentityHeader, bump);
+pub const IS_FROZEN_OFFSET: usize = offset_of!(QuantumIdentityHeader, is_frozen);
+pub const IS_SEALED_OFFSET: usize = offset_of!(QuantumIdentityHeader, is_sealed);
+pub const VERSION_OFFSET: usize = offset_of!(QuantumIdentityHeader, version);
+pub const IS_REVOKED_OFFSET: usize = offset_of!(QuantumIdentityHeader, is_revoked);
+pub const IS_FORWARDED_OFFSET: usize = offset_of!(QuantumIdentityHeader, is_forwarded);
+pub const MINT_POLICY_COUNT_OFFSET: usize = offset_of!(QuantumIdentityHeader, mint_policy_count);
+pub const PQC_PUBLIC_KEY_OFFSET: usize = QUANTUM_IDENTITY_HEADER_LEN;
+/// A forwarding tombstone (`is_forwarded`) has no key; the 32 bytes after
+/// the header hold the authority the identity moved to
+pub const FORWARD_AUTHORITY_OFFSET: usize = QUANTUM_IDENTITY_HEADER_LEN;
+
+// Layout assertions: a reordered, resized or padded field fails the build
+const _: () = {
+    assert!(align_of::<QuantumIdentityHeader>() == 1);
+    assert!(AUTHORITY_OFFSET == 8);
+    assert!(SEQUENCE_OFFSET == 40);
+    assert!(LAST_ACTIVE_SLOT_OFFSET == 48);
+    assert!(CREATED_AT_OFFSET == 56);
+    assert!(THRESHOLD_OFFSET == 64);
+    assert!(WINDOW_START_OFFSET == 72);
+    assert!(WINDOW_AMOUNT_OFFSET == 80);
+    assert!(KEY_VERSION_OFFSET == 88);
+    assert!(KEY_WRITTEN_LEN_OFFSET == 90);
+    assert!(KEY_LEN_OFFSET == 92);
+    assert!(BUMP_OFFSET == 94);
+    assert!(IS_FROZEN_OFFSET == 95);
+    assert!(IS_SEALED_OFFSET == 96);
+    assert!(VERSION_OFFSET == 97);
+    assert!(IS_REVOKED_OFFSET == 98);
+    assert!(IS_FORWARDED_OFFSET == 99);
+    assert!(MINT_POLICY_COUNT_OFFSET == 100);
+    assert!(QUANTUM_IDENTITY_HEADER_LEN == 104);
+};
+
+/// Whether `data` (discriminator already checked) is a `QuantumIdentity` in
+/// the Borsh layout: its key length prefix covers exactly the bytes after
+/// `BORSH_HEADER_LEN`. A versioned account never matches: the same four bytes
+/// hold `key_len`, `bump` and `is_frozen`, which read as `key_len` plus at
+/// least 65,536 for any non-zero bump, while only `key_len + 8` bytes (40 for
+/// a forwarding tombstone) follow them.
+fn is_borsh_identity(data: &[u8]) -> bool {
+    let Some(prefix) = data.get(BORSH_KEY_LEN_OFFSET..BORSH_HEADER_LEN) else {
+        return false;
+    };
+    let key_len = u32::from_le_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize;
+    data.len() == BORSH_HEADER_LEN + key_len
+}
+
+/// Layout version of a `QuantumIdentity` account, or `None` if `data` is not
+/// a `QuantumIdentity` account in any known shape.
+pub fn quantum_identity_version(data: &[u8]) -> Option<u8> {
+    if data.len() < 8 || data[..8] != QUANTUM_IDENTITY_DISCRIMINATOR {
+        return None;
+    }
+    if is_borsh_identity(data) {
+        return Some(QUANTUM_IDENTITY_VERSION_BORSH);
+    }
+    if data.len() < QUANTUM_IDENTITY_HEADER_LEN
+        || data[VERSION_OFFSET] == QUANTUM_IDENTITY_VERSION_BORSH
+    {
+        return None;
+    }
+    Some(data[VERSION_OFFSET])
+}
+
+impl QuantumIdentityHeader {
+    /// Zero-copy view of the header at the start of `data`, or `None` if the
+    /// data is not a `QuantumIdentity` account in the current layout version
+    /// (see `quantum_identity_version` to tell the cases apart).
+    pub fn from_account_data(data: &[u8]) -> Option<&Self> {
+        if quantum_identity_version(data) != Some(QUANTUM_IDENTITY_VERSION) {
+            return None;
+        }
+        // SAFETY: length checked above; the struct is `repr(C)` with
+        // alignment 1 and only byte fields, so every bit pattern is valid.
+        Some(unsafe { &*(data.as_ptr() as *const Self) })
+    }
+
+    pub fn authority(&self) -> [u8; 32] {
+        self.authority
+    }
+
+    pub fn sequence(&self) -> u64 {
+        u64::from_le_bytes(self.sequence)
+    }
+
+    pub fn last_active_slot(&self) -> u64 {
+        u64::from_le_bytes(self.last_active_slot)
+    }
+
+    pub fn created_at(&self) -> i64 {
+        i64::from_le_bytes(self.created_at)
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.is_frozen != 0
+    }
+
+    pub fn threshold_amount(&self) -> u64 {
+        u64::from_le_bytes(self.threshold_amount)
+    }
+
+    pub fn key_version(&self) -> u16 {
+        u16::from_le_bytes(self.key_version)
+    }
+
+    pub fn current_window_start(&self) -> i64 {
+        i64::from_le_bytes(self.current_window_start)
+    }
+
+    pub fn current_window_amount(&self) -> u64 {
+        u64::from_le_bytes(self.current_window_amount)
+    }
+
+    pub fn is_sealed(&self) -> bool {
+        self.is_sealed != 0
+    }
+
+    pub fn key_written_len(&self) -> u16 {
+        u16::from_le_bytes(self.key_written_len)
+    }
+
+    pub fn key_len(&self) -> u16 {
+        u16::from_le_bytes(self.key_len)
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.is_revoked != 0
+    }
+
+    pub fn is_forwarded(&self) -> bool {
+        self.is_forwarded != 0
+    }
+}
+
+// ============================================================================
+// VELOCITY STATE
+// ============================================================================
+
+/// Anchor account discriminator: SHA256("account:VelocityState")[0..8]
+pub const VELOCITY_STATE_DISCRIMINATOR: [u8; 8] = [35, 63, 85, 50, 244, 78, 171, 27];
+
+/// A `VelocityState` account, discriminator included: the 24h velocity
+/// window of one identity on one mint. `quresis` stores it with Borsh, which
+/// for these fixed-size fields is exactly this byte layout.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VelocityStateData {
+    pub discriminator: [u8; 8],
+    pub identity: [u8; 32],
+    pub mint: [u8; 32],
+    pub window_start: [u8; 8],
+    pub window_amount: [u8; 8],
+    pub bump: u8,
+}
+
+/// Bytes of a `VelocityState` account
+pub const VELOCITY_STATE_LEN: usize = size_of::<VelocityStateData>();
+
+pub const VELOCITY_IDENTITY_OFFSET: usize = offset_of!(VelocityStateData, identity);
+pub const VELOCITY_MINT_OFFSET: usize = offset_of!(VelocityStateData, mint);
+pub const VELOCITY_WINDOW_START_OFFSET: usize = offset_of!(VelocityStateData, window_start);
+pub const VELOCITY_WINDOW_AMOUNT_OFFSET: usize = offset_of!(VelocityStateData, window_amount);
+pub const VELOCITY_BUMP_OFFSET: usize = offset_of!(VelocityStateData, bump);
+
+const _: () = {
+    assert!(align_of::<VelocityStateData>() == 1);
+    assert!(VELOCITY_IDENTITY_OFFSET == 8);
+    assert!(VELOCITY_MINT_OFFSET == 40);
+    assert!(VELOCITY_WINDOW_START_OFFSET == 72);
+    assert!(VELOCITY_WINDOW_AMOUNT_OFFSET == 80);
+    assert!(VELOCITY_BUMP_OFFSET == 88);
+    assert!(VELOCITY_STATE_LEN == 89);
+};
+
+impl VelocityStateData {
+    /// Zero-copy view of a `VelocityState` account, or `None` if `data` is
+    /// too short or is not one.
+    pub fn from_account_data(data: &[u8]) -> Option<&Self> {
+        if data.len() < VELOCITY_STATE_LEN || data[..8] != VELOCITY_STATE_DISCRIMINATOR {
+            return None;
+        }
+        // SAFETY: length checked above; alignment 1, byte fields only.
+        Some(unsafe { &*(data.as_ptr() as *const Self) })
+    }
+
+    pub fn identity(&self) -> [u8; 32] {
+        self.identity
+    }
+
+    pub fn mint(&self) -> [u8; 32] {
+        self.mint
+    }
+
+    pub fn window_start(&self) -> i64 {
+        i64::from_le_bytes(self.window_start)
+    }
+
+    pub fn window_amount(&self) -> u64 {
+        u64::from_le_bytes(self.window_amount)
+    }
+}
+
+// ============================================================================
+// MINT POLICY
+// ============================================================================
+
+/// Anchor account discriminator: SHA256("account:MintPolicy")[0..8]
+pub const MINT_POLICY_DISCRIMINATOR: [u8; 8] = [191, 78, 230, 47, 121, 49, 66, 222];
+
+/// A `MintPolicy` account, discriminator included: an identity's threshold
+/// and velocity limit for one mint. Stored with Borsh like `VelocityState`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MintPolicyData {
+    pub discriminator: [u8; 8],
+    pub identity: [u8; 32],
+    pub mint: [u8; 32],
+    pub threshold_amount: [u8; 8],
+    pub velocity_limit: [u8; 8],
+    pub bump: u8,
+}
+
+/// Bytes of a `MintPolicy` account
+pub const MINT_POLICY_LEN: usize = size_of::<MintPolicyData>();
+
+pub const MINT_POLICY_IDENTITY_OFFSET: usize = offset_of!(MintPolicyData, identity);
+pub const MINT_POLICY_MINT_OFFSET: usize = offset_of!(MintPolicyData, mint);
+pub const MINT_POLICY_THRESHOLD_OFFSET: usize = offset_of!(MintPolicyData, threshold_amount);
+pub const MINT_POLICY_VELOCITY_LIMIT_OFFSET: usize = offset_of!(MintPolicyData, velocity_limit);
+pub const MINT_POLICY_BUMP_OFFSET: usize = offset_of!(MintPolicyData, bump);
+
+const _: () = {
+    assert!(align_of::<MintPolicyData>() == 1);
+    assert!(MINT_POLICY_IDENTITY_OFFSET == 8);
+    assert!(MINT_POLICY_MINT_OFFSET == 40);
+    assert!(MINT_POLICY_THRESHOLD_OFFSET == 72);
+    assert!(MINT_POLICY_VELOCITY_LIMIT_OFFSET == 80);
+    assert!(MINT_POLICY_BUMP_OFFSET == 88);
+    assert!(MINT_POLICY_LEN == 89);
+};
+
+impl MintPolicyData {
+    /// Zero-copy view of a `MintPolicy` account, or `None` if `data` is too
+    /// short or is not one.
+    pub fn from_account_data(data: &[u8]) -> Option<&Self> {
+        if data.len() < MINT_POLICY_LEN || data[..8] != MINT_POLICY_DISCRIMINATOR {
+            return None;
+        }
+        // SAFETY: length checked above; alignment 1, byte fields only.
+        Some(unsafe { &*(data.as_ptr() as *const Self) })
+    }
+
+    pub fn identity(&self) -> [u8; 32] {
+        self.identity
+    }
+
+    pub fn mint(&self) -> [u8; 32] {
+        self.mint
+    }
+
+    pub fn threshold_amount(&self) -> u64 {
+        u64::from_le_bytes(self.threshold_amount)
+    }
+
+    pub fn velocity_limit(&self) -> u64 {
+        u64::from_le_bytes(self.velocity_limit)
+    }
+}
+
+// ============================================================================
+// THRESHOLD UNITS
+// ============================================================================
+
+/// Decimals of the unit `threshold_amount` and the velocity window are kept
+/// in: 10^9 threshold units are one whole token of any mint (lamports for
+/// SOL), so one identity means the same amount on every mint it sends.
+pub const THRESHOLD_DECIMALS: u8 = 9;
+
+/// Convert `amount` raw units of a mint with `decimals` decimals to threshold
+/// units. Scaling down rounds up so dust never slips under a threshold;
+/// scaling up saturates at `u64::MAX`.
+pub const fn normalize_amount(amount: u64, decimals: u8) -> u64 {
+    if decimals <= THRESHOLD_DECIMALS {
+        // At most 10^9, cannot overflow
+        let scale = 10u64.pow((THRESHOLD_DECIMALS - decimals) as u32);
+        return amount.saturating_mul(scale);
+    }
+    match 10u64.checked_pow((decimals - THRESHOLD_DECIMALS) as u32) {
+        Some(scale) => amount.div_ceil(scale),
+        // More than 19 extra decimals: any non-zero amount is below one unit
+        None => (amount != 0) as u64,
+    }
+}
+
+/// `const` slice equality, for discriminator assertions in dependent crates
+pub const fn bytes_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
@@ -1,4 +1,12 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as ix_sysvar;
+use anchor_lang::Discriminator;
+use quresis_common::{
+    normalize_amount, quantum_identity_version, MintPolicyData, QuantumIdentityHeader,
+    VelocityStateData,
+    FORWARD_AUTHORITY_OFFSET, QUANTUM_IDENTITY_HEADER_LEN, QUANTUM_IDENTITY_VERSION_BORSH,
+    THRESHOLD_DECIMALS, VELOCITY_STATE_LEN,
+};
 
 declare_id!("9P6cDkGwt3AADtVtFLy3nCHz3ZDLnMLpscUmVFqosvB4");
 
@@ -24,12 +32,29 @@ declare_id!("9P6cDkGwt3AADtVtFLy3nCHz3ZDLnMLpscUmVFqosvB4");
 // parses its data as a TLV (Type-Length-Value) blob containing a list of
 // ExtraAccountMeta structures. Each entry (35 bytes) encodes either:
 //   - A literal pubkey (discriminator = 0x00)
-//   - A PDA with seeds (discriminator >= 0xe7, with seed spec following)
+//   - A PDA of another program (discriminator = 128 + index of that program's
+//     account, with the seed spec packed into the address field)
 //
-// For our Quantum Guard, we declare 3 extra accounts:
+// For our Quantum Guard, we declare 10 extra accounts:
 //   [0] hook_config PDA  [b"quresis_hook", mint]   → writable, tracks stats
-//   [1] sender_identity  [b"quresis_id", owner]     → read-only, threshold check
-//   [2] quresis-core program (literal pubkey)        → read-only, for PDA deriv
+//       and funds new velocity states
+//   [1] quresis-core program (literal pubkey)        → read-only, for PDA deriv
+//   [2] sender_identity  [b"quresis_id", owner] @ [1] → writable, threshold
+//       check
+//   [3] transfer_authorization [b"quresis_auth", identity, mint] @ [1]
+//       → writable, one-time HardEnforce receipt consumed via CPI
+//   [4] Instructions sysvar (literal pubkey) → read-only, same-transaction
+//       verify_signature introspection
+//   [5] hook_signer PDA [b"quresis_caller"]   → writable, signs core CPIs
+//       and pays for new velocity states
+//   [6] approved_hook [b"quresis_approved_hook", this program] @ quresis-core
+//       → read-only, proves this hook is in core's caller registry
+//   [7] velocity_state [b"quresis_velocity", identity, mint] @ quresis-core
+//       → writable, the sender's 24h window on this mint
+//   [8] System program (literal pubkey) → creates velocity states
+//   [9] mint_policy [b"quresis_mint_policy", identity, mint] @ quresis-core
+//       → read-only, the sender's threshold override for this mint
+//   [10..] issuer policy accounts, set with `update_extra_account_meta_list`
 //
 // ## Architecture Notes
 //
@@ -56,89 +81,92 @@ pub mod quresis_hook {
     pub fn initialize_extra_account_meta_list(
         ctx: Context<InitializeExtraAccountMetaList>,
         enforcement_mode: EnforcementMode,
+        failure_policy: FailurePolicy,
     ) -> Result<()> {
         // ── 1. Initialize HookConfig ──────────────────────────────────────────
         let hook_config = &mut ctx.accounts.hook_config;
         hook_config.mint = ctx.accounts.mint.key();
         hook_config.authority = ctx.accounts.authority.key();
         hook_config.enforcement_mode = enforcement_mode;
+        hook_config.failure_policy = failure_policy;
         hook_config.total_transfers_checked = 0;
         hook_config.high_value_transfers_detected = 0;
         hook_config.bump = ctx.bumps.hook_config;
         hook_config.extra_meta_bump = ctx.bumps.extra_account_meta_list;
 
         // ── 2. Write ExtraAccountMetaList TLV into the PDA ───────────────────
-        //
-        // The SPL runtime parses the PDA data as:
-        //   [0..4]  u32 LE  = SPL "ExtraAccountMetaList" type discriminator
-        //   [4..8]  u32 LE  = byte length of the entries that follow
-        //   [8..N]  []ExtraAccountMeta = tightly packed entries
-        //
-        // Each ExtraAccountMeta entry = 35 bytes:
-        //   [0]      u8           = discriminator
-        //   [1..33]  [u8; 32]     = address_config (pubkey or packed seeds)
-        //   [33]     bool         = is_signer
-        //   [34]     bool         = is_writable
-
         let mint_key = ctx.accounts.mint.key();
+        let extra_meta_account = &ctx.accounts.extra_account_meta_list;
+        write_extra_account_metas(&mut extra_meta_account.try_borrow_mut_data()?, &mint_key, &[])?;
+
+        msg!("✅ Quresis Quantum Guard — Initialized!");
+        msg!("   Mint: {}", mint_key);
+        msg!("   HookConfig PDA: {}", ctx.accounts.hook_config.key());
+        msg!("   ExtraAccountMetaList: {}", extra_meta_account.key());
+        msg!("   Enforcement Mode: {:?}", enforcement_mode);
+        msg!("   Failure Policy: {:?}", failure_policy);
+
+        Ok(())
+    }
 
-        // Resolve hook_config PDA address (to store as literal)
-        let (hook_config_pda, _) = Pubkey::find_program_address(
-            &[b"quresis_hook", mint_key.as_ref()],
-            &crate::ID,
+    /// Rewrite the ExtraAccountMetaList (authority-only).
+    ///
+    /// The `GUARD_EXTRA_METAS` Quantum Guard entries are always rewritten
+    /// first, in the order `execute` expects; `policy_metas` replaces
+    /// everything after them. To add, remove or reorder issuer policy
+    /// accounts, pass the full new list. The PDA
+    /// is resized to fit and `execute` receives the policy accounts as
+    /// remaining accounts, so existing mints pick them up without redeploying.
+    pub fn update_extra_account_meta_list(
+        ctx: Context<UpdateExtraAccountMetaList>,
+        policy_metas: Vec<ExtraAccountMetaEntry>,
+    ) -> Result<()> {
+        require!(
+            policy_metas.len() <= MAX_POLICY_EXTRA_METAS,
+            QuresisHookError::TooManyExtraAccountMetas
         );
+        for (position, meta) in policy_metas.iter().enumerate() {
+            let account_index = FIRST_POLICY_ACCOUNT_INDEX + position;
+            require!(
+                is_resolvable_meta(meta, account_index),
+                QuresisHookError::InvalidExtraAccountMeta
+            );
+        }
 
+        // Anchor's `realloc` constraint needs a typed account, so resize by hand:
+        // top up rent from the authority when growing, refund it when shrinking.
+        let mint = ctx.accounts.hook_config.mint;
         let extra_meta_account = &ctx.accounts.extra_account_meta_list;
-        let mut data = extra_meta_account.try_borrow_mut_data()?;
-
-        // Type discriminator for ExtraAccountMetaList TLV
-        // = first 4 bytes of SHA256("spl-transfer-hook-interface:ExtraAccountMetas")
-        // Actual value (from spl source): 0x0a, 0x42, 0x6e, 0x1b
-        let type_disc: [u8; 4] = [0x0a, 0x42, 0x6e, 0x1b];
-
-        // 3 entries × 35 bytes each = 105 bytes
-        const ENTRY_SIZE: usize = 35;
-        const NUM_ENTRIES: usize = 3;
-        let data_len: u32 = (NUM_ENTRIES * ENTRY_SIZE) as u32;
-
-        // Write TLV header
-        data[0..4].copy_from_slice(&type_disc);
-        data[4..8].copy_from_slice(&data_len.to_le_bytes());
-
-        // ── Entry [0]: hook_config PDA (literal address, writable) ───────────
-        // discriminator 0x00 = literal pubkey
-        let off0 = 8;
-        data[off0] = 0x00;
-        data[off0 + 1..off0 + 33].copy_from_slice(hook_config_pda.as_ref());
-        data[off0 + 33] = 0; // is_signer = false
-        data[off0 + 34] = 1; // is_writable = true
-
-        // ── Entry [1]: sender_identity (zero — dynamic seed resolution) ────────
-        // For MVP: stored as zero pubkey. In full implementation, we'd use
-        // the PDA seed resolution format (discriminator 0xe6..0xff for PDAs).
-        // The test suite passes this account explicitly via remaining_accounts.
-        //
-        // discriminator 0x00 = literal pubkey (zero = placeholder)
-        let off1 = off0 + ENTRY_SIZE;
-        data[off1] = 0x00;
-        for b in &mut data[off1 + 1..off1 + 33] {
-            *b = 0;
+        let new_len = extra_account_meta_space(policy_metas.len());
+        let rent_needed = Rent::get()?.minimum_balance(new_len);
+        let rent_held = extra_meta_account.lamports();
+
+        if rent_needed > rent_held {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: extra_meta_account.clone(),
+                    },
+                ),
+                rent_needed - rent_held,
+            )?;
+        } else if rent_held > rent_needed {
+            extra_meta_account.sub_lamports(rent_held - rent_needed)?;
+            ctx.accounts.authority.add_lamports(rent_held - rent_needed)?;
         }
-        data[off1 + 33] = 0; // is_signer = false
-        data[off1 + 34] = 1; // is_writable = true (need to mutate velocity via CPI)
+        extra_meta_account.resize(new_len)?;
 
-        // ── Entry [2]: quresis-core program ID (literal, read-only) ─────────
-        let off2 = off1 + ENTRY_SIZE;
-        data[off2] = 0x00;
-        data[off2 + 1..off2 + 33].copy_from_slice(quresis::ID.as_ref());
-        data[off2 + 33] = 0; // is_signer = false
-        data[off2 + 34] = 0; // is_writable = false
+        write_extra_account_metas(&mut extra_meta_account.try_borrow_mut_data()?, &mint, &policy_metas)?;
 
-        msg!("✅ Quresis Quantum Guard — Initialized!");
-        msg!("   Mint: {}", mint_key);
-        msg!("   HookConfig PDA: {}", hook_config_pda);
-        msg!("   ExtraAccountMetaList: {}", extra_meta_account.key());
-        msg!("   Enforcement Mode: {:?}", enforcement_mode);
+        emit!(ExtraAccountMetaListUpdated {
+            mint,
+            policy_metas: policy_metas.len() as u8,
+            updated_by: ctx.accounts.authority.key(),
+        });
+
+        msg!("🧩 ExtraAccountMetaList updated: {} policy account(s)", policy_metas.len());
 
         Ok(())
     }
@@ -157,16 +185,31 @@ pub mod quresis_hook {
     ///
     /// Extra accounts (auto-appended by runtime from ExtraAccountMetaList):
     ///   5 = hook_config PDA        [b"quresis_hook", mint]
-    ///   6 = sender_identity PDA    [b"quresis_id", source_owner] @ quresis-core
-    ///   7 = quresis_core program
+    ///   6 = quresis_core program
+    ///   7 = sender_identity PDA    [b"quresis_id", source_owner] @ quresis-core
+    ///   8 = transfer_authorization [b"quresis_auth", sender_identity, mint] @ quresis-core
+    ///   9 = instructions sysvar
+    ///  10 = hook_signer PDA       [b"quresis_caller"]
+    ///  11 = approved_hook PDA     [b"quresis_approved_hook", this program] @ quresis-core
+    ///  12 = velocity_state PDA    [b"quresis_velocity", sender_identity, mint] @ quresis-core
+    ///  13 = system program
+    ///  14 = mint_policy PDA       [b"quresis_mint_policy", sender_identity, mint] @ quresis-core
+    ///  15.. = issuer policy accounts (remaining accounts, see `update_extra_account_meta_list`)
     pub fn execute(ctx: Context<Execute>, amount: u64) -> Result<()> {
         let hook_config = &mut ctx.accounts.hook_config;
         let sender_identity = &ctx.accounts.sender_identity;
+        let signer_seeds: &[&[&[u8]]] = &[&[HOOK_CALLER_SEED, &[ctx.bumps.hook_signer]]];
 
         msg!("🛡️ Quresis Quantum Guard — Transfer Intercepted");
         msg!("   Mint:   {}", hook_config.mint);
         msg!("   Amount: {} raw units", amount);
 
+        // ── Step 0: Only a real Token-2022 transfer of this mint may get here ──
+        // Token-2022 sets `TransferHookAccount.transferring` on both token
+        // accounts for the duration of the hook CPI; direct calls can't fake it.
+        require_transferring(&ctx.accounts.source_token_account, &hook_config.mint)?;
+        require_transferring(&ctx.accounts.destination_token_account, &hook_config.mint)?;
+
         // Increment global transfer counter
         hook_config.total_transfers_checked = hook_config
             .total_transfers_checked
@@ -174,79 +217,116 @@ pub mod quresis_hook {
             .unwrap_or(u64::MAX);
 
         // ── Step 1: Opt-in check — does this sender have a Quantum Identity? ──
+        let failure_policy = hook_config.failure_policy;
         if sender_identity.data_is_empty() {
+            if !failure_policy.allows_unregistered() {
+                msg!("❌ BLOCKED: No Quantum Identity registered ({:?})", failure_policy);
+                return Err(QuresisHookError::IdentityRequired.into());
+            }
             msg!("   ℹ️ No Quantum Identity registered — ALLOWED (opt-in protocol)");
             return Ok(());
         }
 
-        // ── Step 2: Zero-Copy byte parsing (no Borsh = ~60% CU savings) ──────
+        // ── Step 2: Zero-copy header view (no Borsh = ~60% CU savings) ──────
         //
-        // QuantumIdentity on-chain layout (from quresis/src/lib.rs):
-        //   Offset 00..08  discriminator    [u8; 8]
-        //   Offset 08..40  authority         Pubkey (32 bytes)
-        //   Offset 40      bump              u8
-        //   Offset 41..49  sequence          u64 (LE)
-        //   Offset 49..57  last_active_slot  u64 (LE)
-        //   Offset 57..65  created_at        i64 (LE)
-        //   Offset 66..74  threshold_amount  u64 (LE)
-        //   Offset 74..76  key_version       u16 (LE)
-        //   Offset 76..84  current_window_start   i64 (LE)
-        //   Offset 84..92  current_window_amount  u64 (LE)
-        //   Offset 92+     pqc_public_key    Vec<u8> (4-byte len prefix + data)
-
-        const MIN_SIZE: usize = 92; // up to current_window_amount field
-        const IS_FROZEN_OFFSET: usize = 65;
-        const THRESHOLD_OFFSET: usize = 66;
-        const WINDOW_START_OFFSET: usize = 76;
-        const WINDOW_AMOUNT_OFFSET: usize = 84;
-
+        // The QuantumIdentity layout lives in `quresis_common`, and quresis-core
+        // asserts at compile time that it matches its zero-copy account.
         let identity_data = sender_identity.try_borrow_data()?;
 
-        if identity_data.len() < MIN_SIZE {
+        if identity_data.len() < QUANTUM_IDENTITY_HEADER_LEN {
+            if !failure_policy.allows_malformed() {
+                msg!("❌ BLOCKED: Identity data too short ({} bytes)", identity_data.len());
+                return Err(QuresisHookError::MalformedIdentity.into());
+            }
             msg!("   ⚠️ Identity data too short ({} bytes) — ALLOWED (defensive)", identity_data.len());
             return Ok(());
         }
 
         // ── Step 3: Discriminator validation (anti-type-confusion) ───────────
-        // discriminator = first 8 bytes of SHA256("account:QuantumIdentity")
-        // = [22, 56, 98, 16, 99, 95, 244, 76]
-        const QUANTUM_IDENTITY_DISCRIMINATOR: [u8; 8] = [22, 56, 98, 16, 99, 95, 244, 76];
-
-        let disc: &[u8; 8] = identity_data[0..8]
-            .try_into()
-            .map_err(|_| QuresisHookError::InvalidIdentityData)?;
-
-        if disc != &QUANTUM_IDENTITY_DISCRIMINATOR {
+        let Some(version) = quantum_identity_version(&identity_data) else {
+            if !failure_policy.allows_malformed() {
+                msg!("❌ BLOCKED: Discriminator mismatch — account is not a QuantumIdentity");
+                return Err(QuresisHookError::MalformedIdentity.into());
+            }
             msg!("   ⚠️ Discriminator mismatch — account is not a QuantumIdentity. ALLOWED.");
             return Ok(());
+        };
+
+        // ── Step 3b: Layout version — fail closed on layouts we cannot read ──
+        let Some(identity) = QuantumIdentityHeader::from_account_data(&identity_data) else {
+            if version == QUANTUM_IDENTITY_VERSION_BORSH {
+                msg!("❌ BLOCKED: Quantum Identity uses the legacy layout — run quresis::migrate_identity");
+                return Err(QuresisHookError::IdentityNeedsMigration.into());
+            }
+            msg!("❌ BLOCKED: Unknown Quantum Identity layout version {}", version);
+            return Err(QuresisHookError::UnsupportedIdentityVersion.into());
+        };
+
+        // ── Step 4: Revoked / sealed / frozen identity checks ────────────────
+        // A closed identity leaves a tombstone: the wallet opted in, so it
+        // stays blocked instead of falling back to "unregistered"
+        if identity.is_forwarded() {
+            // `migrate_authority` moved the identity off this (compromised) wallet
+            let moved_to = identity_data
+                .get(FORWARD_AUTHORITY_OFFSET..FORWARD_AUTHORITY_OFFSET + 32)
+                .and_then(|bytes| Pubkey::try_from(bytes).ok())
+                .unwrap_or_default();
+            msg!("❌ BLOCKED: Quantum Identity moved to {}", moved_to);
+            return Err(QuresisHookError::IdentityForwarded.into());
         }
 
-        // ── Step 4: Frozen identity check ─────────────────────────────────────
-        let is_frozen = identity_data[IS_FROZEN_OFFSET] == 1;
-        if is_frozen {
-            msg!("❌ BLOCKED: Quantum Identity is FROZEN");
-            return Err(QuresisHookError::IdentityFrozen.into());
+        if identity.is_revoked() {
+            msg!("❌ BLOCKED: Quantum Identity was revoked");
+            return Err(QuresisHookError::IdentityRevoked.into());
         }
 
-        // ── Step 5: Read threshold and velocity amount (little-endian) ────────
-        let threshold_bytes: &[u8; 8] = identity_data[THRESHOLD_OFFSET..THRESHOLD_OFFSET + 8]
-            .try_into()
-            .map_err(|_| QuresisHookError::InvalidIdentityData)?;
-        let threshold = u64::from_le_bytes(*threshold_bytes);
+        // A staged registration whose key upload is unfinished cannot be used
+        if !identity.is_sealed() {
+            msg!("❌ BLOCKED: Quantum Identity registration is not sealed");
+            return Err(QuresisHookError::IdentityNotSealed.into());
+        }
 
-        let window_start_bytes: &[u8; 8] = identity_data[WINDOW_START_OFFSET..WINDOW_START_OFFSET + 8]
-            .try_into()
-            .map_err(|_| QuresisHookError::InvalidIdentityData)?;
-        let window_start = i64::from_le_bytes(*window_start_bytes);
+        if identity.is_frozen() {
+            msg!("❌ BLOCKED: Quantum Identity is FROZEN");
+            return Err(QuresisHookError::IdentityFrozen.into());
+        }
 
-        let window_amount_bytes: &[u8; 8] = identity_data[WINDOW_AMOUNT_OFFSET..WINDOW_AMOUNT_OFFSET + 8]
-            .try_into()
-            .map_err(|_| QuresisHookError::InvalidIdentityData)?;
-        let mut window_amount = u64::from_le_bytes(*window_amount_bytes);
+        // ── Step 5: Read thresholds and this mint's velocity window ──────────
+        let identity_threshold = identity.threshold_amount();
+        let sequence = identity.sequence();
 
         // Drop borrow BEFORE making CPI
         drop(identity_data);
 
+        // A MintPolicy overrides the identity threshold for this mint; without
+        // one, the identity threshold bounds the window total (and therefore
+        // any single transfer)
+        let mint_policy = &ctx.accounts.mint_policy;
+        let (threshold, velocity_limit) = if mint_policy.data_is_empty() {
+            (identity_threshold, identity_threshold)
+        } else {
+            let policy_data = mint_policy.try_borrow_data()?;
+            let policy = MintPolicyData::from_account_data(&policy_data)
+                .ok_or(QuresisHookError::InvalidMintPolicy)?;
+            msg!("   ✓ Mint policy found — overrides identity threshold {}", identity_threshold);
+            (policy.threshold_amount(), policy.velocity_limit())
+        };
+
+        // Empty until the sender's first recorded transfer of this mint
+        let velocity_state = &ctx.accounts.velocity_state;
+        let (window_start, mut window_amount) = if velocity_state.data_is_empty() {
+            (0, 0)
+        } else {
+            let velocity_data = velocity_state.try_borrow_data()?;
+            let velocity = VelocityStateData::from_account_data(&velocity_data)
+                .ok_or(QuresisHookError::InvalidVelocityState)?;
+            (velocity.window_start(), velocity.window_amount())
+        };
+
+        // Thresholds are in THRESHOLD_DECIMALS units, not this mint's raw units
+        let decimals = mint_decimals(&ctx.accounts.mint)?;
+        let normalized_amount = normalize_amount(amount, decimals);
+
         // Velocity Reset Logic in Hook
         let current_time = Clock::get()?.unix_timestamp;
         let window_size: i64 = 24 * 60 * 60; // 24 hours
@@ -254,25 +334,44 @@ pub mod quresis_hook {
             window_amount = 0;
         }
 
-        let new_total = window_amount.saturating_add(amount);
+        let new_total = window_amount.saturating_add(normalized_amount);
 
-        msg!("   ✓ QuantumIdentity valid | frozen=false | threshold={}", threshold);
-        msg!("   ✓ Current Window Velocity: {} + Amount: {} = New Total: {}", window_amount, amount, new_total);
+        msg!(
+            "   ✓ QuantumIdentity valid | frozen=false | threshold={} | velocity_limit={}",
+            threshold,
+            velocity_limit
+        );
+        msg!(
+            "   ✓ Amount: {} raw units ({} decimals) = {} units ({} decimals)",
+            amount,
+            decimals,
+            normalized_amount,
+            THRESHOLD_DECIMALS
+        );
+        msg!("   ✓ Current Window Velocity: {} + Amount: {} = New Total: {}", window_amount, normalized_amount, new_total);
 
         // ── Step 6: Quantum Guard enforcement ─────────────────────────────────
-        if new_total >= threshold {
+        if normalized_amount >= threshold || new_total >= velocity_limit {
             hook_config.high_value_transfers_detected = hook_config
                 .high_value_transfers_detected
                 .checked_add(1)
                 .unwrap_or(u64::MAX);
 
-            msg!("⚠️  HIGH-VALUE TRANSFER: {} >= threshold {}", new_total, threshold);
+            msg!(
+                "⚠️  HIGH-VALUE TRANSFER: amount {} (threshold {}), window {} (limit {})",
+                normalized_amount,
+                threshold,
+                new_total,
+                velocity_limit
+            );
 
             emit!(HighValueTransferDetected {
                 mint: hook_config.mint,
                 sender: ctx.accounts.source_owner.key(),
                 amount,
+                normalized_amount,
                 threshold,
+                velocity_limit,
                 identity_pda: sender_identity.key(),
                 enforcement_mode: hook_config.enforcement_mode,
             });
@@ -286,25 +385,78 @@ pub mod quresis_hook {
                     msg!("   ⚡ Production: ML-DSA dual-signature would be required here");
                 }
                 EnforcementMode::HardEnforce => {
-                    msg!("❌ [HARD ENFORCE] BLOCKED!");
-                    msg!("   ML-DSA quantum signature required for accumulated transfers >= {}", threshold);
-                    msg!("   Authorize via quresis::verify_signature CPI first");
-                    return Err(QuresisHookError::QuantumSignatureRequired.into());
+                    // Signed amount caps are per mint, so they stay in raw units
+                    if authorized_in_transaction(ctx.accounts, amount, sequence)? {
+                        msg!("   [HARD ENFORCE] ALLOWED — verify_signature in this transaction");
+                    } else if !has_unspent_authorization(&ctx.accounts.transfer_authorization)? {
+                        msg!("❌ [HARD ENFORCE] BLOCKED!");
+                        msg!(
+                            "   ML-DSA quantum signature required for transfers >= {} or accumulated transfers >= {}",
+                            threshold,
+                            velocity_limit
+                        );
+                        msg!("   Authorize via quresis::verify_signature first");
+                        return Err(QuresisHookError::QuantumSignatureRequired.into());
+                    } else {
+                        // Core checks destination, cap and expiry, then marks the receipt consumed
+                        let cpi_ctx = CpiContext::new_with_signer(
+                            ctx.accounts.quresis_program.to_account_info(),
+                            quresis::cpi::accounts::ConsumeTransferAuthorization {
+                                identity: sender_identity.clone(),
+                                transfer_authorization: ctx.accounts.transfer_authorization.clone(),
+                                mint: ctx.accounts.mint.clone(),
+                                destination: ctx.accounts.destination_token_account.clone(),
+                                approved_hook: ctx.accounts.approved_hook.clone(),
+                                hook_signer: ctx.accounts.hook_signer.clone(),
+                            },
+                            signer_seeds,
+                        );
+                        quresis::cpi::consume_transfer_authorization(cpi_ctx, amount)?;
+                        msg!("   [HARD ENFORCE] ALLOWED — quantum authorization consumed");
+                    }
                 }
             }
         } else {
-            msg!("✅ ALLOWED — {} < threshold {}", new_total, threshold);
+            msg!(
+                "✅ ALLOWED — {} < threshold {}, {} < limit {}",
+                normalized_amount,
+                threshold,
+                new_total,
+                velocity_limit
+            );
         }
 
         // ── Step 7: Record the transfer velocity via CPI to quresis-core ──────
         // If we reach here, the transfer is permitted (either under threshold, or SoftEnforce).
+        if velocity_state.data_is_empty()
+            && !fund_velocity_state(
+                &ctx.accounts.hook_config,
+                &ctx.accounts.hook_signer,
+                velocity_state,
+            )?
+        {
+            // Core would create the sender's VelocityState with the hook
+            // signer's lamports, but this mint's HookConfig cannot pay for it
+            if !failure_policy.allows_unrecorded_velocity() {
+                msg!("❌ BLOCKED: No VelocityState — call quresis::init_velocity_state or top up the HookConfig");
+                return Err(QuresisHookError::InsufficientVelocityFunds.into());
+            }
+            msg!("   ⚠️ No VelocityState and HookConfig cannot fund one — ALLOWED, velocity not recorded");
+            return Ok(());
+        }
+
         let cpi_program = ctx.accounts.quresis_program.to_account_info();
         let cpi_accounts = quresis::cpi::accounts::RecordTransfer {
             identity: sender_identity.clone(),
+            velocity_state: velocity_state.clone(),
+            mint: ctx.accounts.mint.clone(),
+            approved_hook: ctx.accounts.approved_hook.clone(),
+            hook_signer: ctx.accounts.hook_signer.clone(),
+            system_program: ctx.accounts.system_program.to_account_info(),
         };
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        
-        quresis::cpi::record_transfer(cpi_ctx, amount)?;
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        quresis::cpi::record_transfer(cpi_ctx, normalized_amount)?;
         msg!("✅ Velocity recorded on-chain via CPI");
 
         Ok(())
@@ -332,6 +484,83 @@ pub mod quresis_hook {
         Ok(())
     }
 
+    /// Update the failure policy (authority-only): how `execute` treats a
+    /// sender without a usable Quantum Identity
+    pub fn update_failure_policy(
+        ctx: Context<UpdateHookConfig>,
+        new_policy: FailurePolicy,
+    ) -> Result<()> {
+        let hook_config = &mut ctx.accounts.hook_config;
+        let old_policy = hook_config.failure_policy;
+
+        hook_config.failure_policy = new_policy;
+
+        emit!(FailurePolicyUpdated {
+            mint: hook_config.mint,
+            old_policy,
+            new_policy,
+            updated_by: ctx.accounts.authority.key(),
+        });
+
+        msg!("📊 Failure Policy: {:?} → {:?}", old_policy, new_policy);
+
+        Ok(())
+    }
+
+    /// Bring a HookConfig created before `failure_policy` existed up to the
+    /// current layout (authority-only). The account grows by one byte, paid
+    /// by the authority, and gets `AllowUnregistered`, the behaviour those
+    /// mints had before the policy was added.
+    pub fn migrate_hook_config(ctx: Context<MigrateHookConfig>) -> Result<()> {
+        let hook_config = ctx.accounts.hook_config.to_account_info();
+        {
+            let data = hook_config.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == *HookConfig::DISCRIMINATOR,
+                ErrorCode::AccountDiscriminatorMismatch
+            );
+            require!(
+                data.len() != 8 + HookConfig::SPACE,
+                QuresisHookError::HookConfigUpToDate
+            );
+            require!(
+                data.len() == 8 + HookConfig::LEGACY_SPACE,
+                ErrorCode::AccountDidNotDeserialize
+            );
+            // mint (32) precedes the authority
+            require!(
+                data[40..72] == ctx.accounts.authority.key().to_bytes(),
+                ErrorCode::ConstraintHasOne
+            );
+        }
+
+        let required = Rent::get()?.minimum_balance(8 + HookConfig::SPACE);
+        let current = hook_config.lamports();
+        if required > current {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: hook_config.clone(),
+                    },
+                ),
+                required - current,
+            )?;
+        }
+        hook_config.resize(8 + HookConfig::SPACE)?;
+        hook_config.try_borrow_mut_data()?[8 + HookConfig::LEGACY_SPACE] =
+            FailurePolicy::AllowUnregistered as u8;
+
+        msg!(
+            "🧬 HookConfig migrated for mint {}: Failure Policy {:?}",
+            ctx.accounts.mint.key(),
+            FailurePolicy::AllowUnregistered
+        );
+
+        Ok(())
+    }
+
     /// Read-only statistics (emits logs, no state change)
     pub fn get_statistics(ctx: Context<GetStatistics>) -> Result<()> {
         let c = &ctx.accounts.hook_config;
@@ -339,11 +568,32 @@ pub mod quresis_hook {
         msg!("📊 ═══ Quresis Quantum Guard Statistics ═══");
         msg!("   Mint:                    {}", c.mint);
         msg!("   Enforcement Mode:        {:?}", c.enforcement_mode);
+        msg!("   Failure Policy:          {:?}", c.failure_policy);
         msg!("   Total Transfers Checked: {}", c.total_transfers_checked);
         msg!("   High-Value Transfers:    {}", c.high_value_transfers_detected);
 
         Ok(())
     }
+
+    /// SPL Transfer Hook interface dispatcher.
+    ///
+    /// Token-2022 does not use Anchor's `global:execute` discriminator: it
+    /// invokes the hook with `SPL_EXECUTE_DISCRIMINATOR || amount (u64 LE)`.
+    /// Anchor hands any unmatched instruction data to this fallback, which
+    /// routes `Execute` into the regular `execute` handler (same accounts,
+    /// same checks) and rejects everything else.
+    pub fn fallback<'info>(
+        program_id: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+        data: &[u8],
+    ) -> Result<()> {
+        let amount = data
+            .strip_prefix(&SPL_EXECUTE_DISCRIMINATOR)
+            .and_then(|rest| rest.get(..8))
+            .ok_or(anchor_lang::error::ErrorCode::InstructionFallbackNotFound)?;
+
+        __private::__global::execute(program_id, accounts, amount)
+    }
 }
 
 // ============================================================================
@@ -389,6 +639,30 @@ pub struct InitializeExtraAccountMetaList<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Rewrites (and resizes) the ExtraAccountMetaList of an initialized mint.
+#[derive(Accounts)]
+pub struct UpdateExtraAccountMetaList<'info> {
+    #[account(
+        seeds = [b"quresis_hook", hook_config.mint.as_ref()],
+        bump = hook_config.bump,
+        has_one = authority,
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+
+    /// CHECK: We write raw TLV bytes manually (no Anchor account type)
+    #[account(
+        mut,
+        seeds = [b"extra-account-metas", hook_config.mint.as_ref()],
+        bump = hook_config.extra_meta_bump,
+    )]
+    pub extra_account_meta_list: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
 /// The `execute` instruction — auto-invoked by Token-2022 on every transfer.
 ///
 /// Account indices MUST match what we declared in ExtraAccountMetaList.
@@ -398,15 +672,15 @@ pub struct Execute<'info> {
     // ── Base accounts (provided by Token-2022 runtime in fixed order) ─────
 
     /// Source token account (ATA of sender)
-    /// CHECK: Validated by Token-2022 runtime
+    /// CHECK: Owner, mint and transferring flag checked in `require_transferring`
     pub source_token_account: AccountInfo<'info>,
 
     /// The RWA mint being transferred
-    /// CHECK: Validated by Token-2022 runtime
+    /// CHECK: Validated by Token-2022 runtime; owner checked in `mint_decimals`
     pub mint: AccountInfo<'info>,
 
     /// Destination token account
-    /// CHECK: Validated by Token-2022 runtime
+    /// CHECK: Owner, mint and transferring flag checked in `require_transferring`
     pub destination_token_account: AccountInfo<'info>,
 
     /// Source wallet (original transaction signer)
@@ -431,6 +705,10 @@ pub struct Execute<'info> {
     )]
     pub hook_config: Account<'info, HookConfig>,
 
+    /// Quresis core program (for cross-program PDA derivation)
+    /// CHECK: We only use this as a program ID reference
+    pub quresis_program: AccountInfo<'info>,
+
     /// Sender's Quantum Identity PDA from quresis-core.
     /// May be uninitialized (empty) if sender hasn't registered — handled gracefully.
     /// CHECK: Validated via discriminator check inside the instruction handler
@@ -442,9 +720,59 @@ pub struct Execute<'info> {
     )]
     pub sender_identity: AccountInfo<'info>,
 
-    /// Quresis core program (for cross-program PDA derivation)
-    /// CHECK: We only use this as a program ID reference
-    pub quresis_program: AccountInfo<'info>,
+    /// Sender's one-time TransferAuthorization PDA from quresis-core.
+    /// Usually uninitialized; only consumed for HardEnforce high-value transfers.
+    /// CHECK: Checked for a spent receipt here, validated by quresis-core during the CPI
+    #[account(
+        mut,
+        seeds = [b"quresis_auth", sender_identity.key().as_ref(), mint.key().as_ref()],
+        bump,
+        seeds::program = quresis::ID,
+    )]
+    pub transfer_authorization: AccountInfo<'info>,
+
+    /// Instructions sysvar, to find a same-transaction verify_signature
+    /// CHECK: Address is checked against the sysvar id
+    #[account(address = ix_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    /// This program's signer PDA, proving to quresis-core that CPIs come from an approved hook.
+    /// Writable: pays for new velocity states with lamports taken from `hook_config`.
+    /// CHECK: Seeds are verified; holds no data
+    #[account(mut, seeds = [HOOK_CALLER_SEED], bump)]
+    pub hook_signer: AccountInfo<'info>,
+
+    /// quresis-core registry entry approving this hook program
+    /// CHECK: Deserialized and validated by quresis-core during the CPI
+    #[account(
+        seeds = [APPROVED_HOOK_SEED, crate::ID.as_ref()],
+        bump,
+        seeds::program = quresis::ID,
+    )]
+    pub approved_hook: AccountInfo<'info>,
+
+    /// Sender's VelocityState PDA for this mint from quresis-core.
+    /// Uninitialized until the sender's first recorded transfer of this mint.
+    /// CHECK: Read through `VelocityStateData`; created and updated by quresis-core during the CPI
+    #[account(
+        mut,
+        seeds = [VELOCITY_SEED, sender_identity.key().as_ref(), mint.key().as_ref()],
+        bump,
+        seeds::program = quresis::ID,
+    )]
+    pub velocity_state: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Sender's MintPolicy PDA for this mint from quresis-core.
+    /// Uninitialized unless the sender set a per-mint threshold.
+    /// CHECK: Seeds are verified; read through `MintPolicyData`
+    #[account(
+        seeds = [MINT_POLICY_SEED, sender_identity.key().as_ref(), mint.key().as_ref()],
+        bump,
+        seeds::program = quresis::ID,
+    )]
+    pub mint_policy: AccountInfo<'info>,
 }
 
 /// Update enforcement mode (authority-only, no transfer hook interaction needed)
@@ -461,6 +789,29 @@ pub struct UpdateHookConfig<'info> {
     pub authority: Signer<'info>,
 }
 
+/// Migrate a HookConfig from the layout without `failure_policy`
+#[derive(Accounts)]
+pub struct MigrateHookConfig<'info> {
+    /// CHECK: Old layout, which `Account<HookConfig>` cannot deserialize;
+    /// discriminator, size and authority are checked by the handler
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"quresis_hook", mint.key().as_ref()],
+        bump,
+    )]
+    pub hook_config: UncheckedAccount<'info>,
+
+    /// CHECK: Only used for the PDA seeds
+    pub mint: UncheckedAccount<'info>,
+
+    /// Must match the stored authority; pays for the extra byte
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
 /// Read-only access to hook statistics
 #[derive(Accounts)]
 pub struct GetStatistics<'info> {
@@ -491,11 +842,15 @@ pub struct HookConfig {
     pub bump: u8,                                  // 1
     /// ExtraAccountMetaList PDA bump
     pub extra_meta_bump: u8,                       // 1
+    /// How senders without a usable Quantum Identity are treated
+    pub failure_policy: FailurePolicy,             // 1
 }
 
 impl HookConfig {
-    /// 32 + 32 + 1 + 8 + 8 + 1 + 1 = 83 bytes
-    pub const SPACE: usize = 83;
+    /// 32 + 32 + 1 + 8 + 8 + 1 + 1 + 1 = 84 bytes
+    pub const SPACE: usize = 84;
+    /// Layout before `failure_policy` was appended; see `migrate_hook_config`
+    pub const LEGACY_SPACE: usize = 83;
 }
 
 /// Quantum Guard enforcement strategy
@@ -509,6 +864,51 @@ pub enum EnforcementMode {
     HardEnforce,
 }
 
+/// What `execute` does when the sender has no usable Quantum Identity
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum FailurePolicy {
+    /// Opt-in: unregistered senders and unreadable identity accounts pass,
+    /// as do transfers whose velocity window the HookConfig cannot pay for
+    /// (they are not recorded)
+    AllowUnregistered,
+    /// Every sender needs a valid, registered identity and a velocity window
+    RequireIdentity,
+    /// Unregistered senders pass, but an identity account that is too short
+    /// or not a QuantumIdentity, or a velocity window nobody paid for,
+    /// blocks the transfer
+    FailClosedOnMalformed,
+}
+
+impl FailurePolicy {
+    /// Whether a sender with no identity account may transfer
+    pub fn allows_unregistered(self) -> bool {
+        self != FailurePolicy::RequireIdentity
+    }
+
+    /// Whether an identity account that cannot be read as a QuantumIdentity
+    /// is ignored (rather than blocking the transfer)
+    pub fn allows_malformed(self) -> bool {
+        self == FailurePolicy::AllowUnregistered
+    }
+
+    /// Whether a transfer may pass without being recorded when the sender
+    /// has no VelocityState for the mint and the HookConfig cannot pay for one
+    pub fn allows_unrecorded_velocity(self) -> bool {
+        self == FailurePolicy::AllowUnregistered
+    }
+}
+
+/// One packed SPL `ExtraAccountMeta`, as stored in the ExtraAccountMetaList
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExtraAccountMetaEntry {
+    /// 0 = literal address, 1 = PDA of this program, 128 + i = PDA of the program at account i
+    pub discriminator: u8,
+    /// Literal pubkey, or packed seeds for PDA entries
+    pub address_config: [u8; 32],
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
 // ============================================================================
 // EVENTS
 // ============================================================================
@@ -517,8 +917,14 @@ pub enum EnforcementMode {
 pub struct HighValueTransferDetected {
     pub mint: Pubkey,
     pub sender: Pubkey,
+    /// Raw units of `mint`
     pub amount: u64,
+    /// `amount` in threshold units (`THRESHOLD_DECIMALS`)
+    pub normalized_amount: u64,
+    /// Single-transfer threshold applied (the mint policy's, if any)
     pub threshold: u64,
+    /// 24h window limit applied (the identity threshold without a mint policy)
+    pub velocity_limit: u64,
     pub identity_pda: Pubkey,
     pub enforcement_mode: EnforcementMode,
 }
@@ -531,6 +937,22 @@ pub struct EnforcementModeUpdated {
     pub updated_by: Pubkey,
 }
 
+#[event]
+pub struct FailurePolicyUpdated {
+    pub mint: Pubkey,
+    pub old_policy: FailurePolicy,
+    pub new_policy: FailurePolicy,
+    pub updated_by: Pubkey,
+}
+
+#[event]
+pub struct ExtraAccountMetaListUpdated {
+    pub mint: Pubkey,
+    /// Issuer policy entries after the Quantum Guard entries
+    pub policy_metas: u8,
+    pub updated_by: Pubkey,
+}
+
 // ============================================================================
 // ERRORS
 // ============================================================================
@@ -554,6 +976,57 @@ pub enum QuresisHookError {
 
     #[msg("Invalid QuantumIdentity data: discriminator mismatch or insufficient length.")]
     InvalidIdentityData,
+
+    #[msg("The sender's Quantum Identity registration is not sealed yet.")]
+    IdentityNotSealed,
+
+    #[msg("The preceding verify_signature does not authorize this transfer.")]
+    TransferAuthorizationMismatch,
+
+    #[msg("Source or destination is not a Token-2022 account of this mint.")]
+    InvalidTokenAccount,
+
+    #[msg("Hook invoked outside a Token-2022 transfer (transferring flag not set).")]
+    NotTransferring,
+
+    #[msg("Extra account meta is malformed or refers to an account that is not resolved yet.")]
+    InvalidExtraAccountMeta,
+
+    #[msg("Too many policy extra account metas.")]
+    TooManyExtraAccountMetas,
+
+    #[msg("Sender's Quantum Identity uses a legacy layout and must be migrated first.")]
+    IdentityNeedsMigration,
+
+    #[msg("Sender's Quantum Identity has a layout version this hook does not support.")]
+    UnsupportedIdentityVersion,
+
+    #[msg("This mint requires every sender to have a registered Quantum Identity.")]
+    IdentityRequired,
+
+    #[msg("Sender's identity account is not a valid QuantumIdentity.")]
+    MalformedIdentity,
+
+    #[msg("Sender's Quantum Identity was revoked; transfers stay blocked until it is re-registered.")]
+    IdentityRevoked,
+
+    #[msg("Sender's Quantum Identity moved to a new wallet; transfers from this one stay blocked.")]
+    IdentityForwarded,
+
+    #[msg("Mint is not an initialized Token-2022 mint.")]
+    InvalidMint,
+
+    #[msg("Sender's velocity account is not a valid VelocityState.")]
+    InvalidVelocityState,
+
+    #[msg("Sender's mint policy account is not a valid MintPolicy.")]
+    InvalidMintPolicy,
+
+    #[msg("HookConfig cannot pay for the sender's VelocityState; call quresis::init_velocity_state or top up the HookConfig account.")]
+    InsufficientVelocityFunds,
+
+    #[msg("HookConfig already uses the current layout.")]
+    HookConfigUpToDate,
 }
 
 // ============================================================================
@@ -562,8 +1035,432 @@ pub enum QuresisHookError {
 
 /// Space for the ExtraAccountMetaList PDA.
 ///
-/// TLV header:  4 (type discriminator) + 4 (data length) = 8 bytes
-/// 3 entries:   3 × 35 = 105 bytes
+/// TLV header:  8 (type discriminator) + 4 (value length) + 4 (entry count) = 16 bytes
+/// 10 entries:  10 × 35 = 350 bytes
 /// Padding:     64 bytes (for future extensions)
-/// Total:       177 bytes
-pub const EXTRA_ACCOUNT_META_SPACE: usize = 8 + (3 * 35) + 64;
+/// Total:       430 bytes
+pub const EXTRA_ACCOUNT_META_SPACE: usize = extra_account_meta_space(0);
+
+/// Quantum Guard entries at the start of every ExtraAccountMetaList
+pub const GUARD_EXTRA_METAS: usize = 10;
+
+/// Upper bound on issuer policy entries, keeping transfers within account limits
+pub const MAX_POLICY_EXTRA_METAS: usize = 16;
+
+/// `execute` account index of the first issuer policy entry
+/// (5 base accounts + the Quantum Guard entries)
+const FIRST_POLICY_ACCOUNT_INDEX: usize = 5 + GUARD_EXTRA_METAS;
+
+/// ExtraAccountMetaList size for `policy_metas` issuer entries (same header and padding)
+pub const fn extra_account_meta_space(policy_metas: usize) -> usize {
+    16 + (GUARD_EXTRA_METAS + policy_metas) * EXTRA_ACCOUNT_META_ENTRY_SIZE + 64
+}
+
+/// Size of one packed `ExtraAccountMeta`
+pub const EXTRA_ACCOUNT_META_ENTRY_SIZE: usize = 35;
+
+/// SPL Transfer Hook `Execute` discriminator:
+/// SHA256("spl-transfer-hook-interface:execute")[0..8]. Also the TLV type of
+/// the ExtraAccountMetaList.
+pub const SPL_EXECUTE_DISCRIMINATOR: [u8; 8] = [105, 37, 101, 197, 75, 251, 102, 26];
+
+/// `execute` account indices referenced by PDA seeds in the ExtraAccountMetaList
+const MINT_ACCOUNT_INDEX: u8 = 1;
+const SOURCE_OWNER_ACCOUNT_INDEX: u8 = 3;
+const QURESIS_PROGRAM_ACCOUNT_INDEX: u8 = 6;
+const SENDER_IDENTITY_ACCOUNT_INDEX: u8 = 7;
+
+/// ExtraAccountMeta discriminator base for a PDA owned by the program at
+/// account index `discriminator - 128`
+const EXTERNAL_PDA_META_BASE: u8 = 128;
+/// ExtraAccountMeta discriminator of a PDA owned by this program
+const OWN_PDA_META: u8 = 1;
+/// Packed seed tags (`spl-tlv-account-resolution` `Seed`)
+const SEED_LITERAL: u8 = 1;
+const SEED_INSTRUCTION_DATA: u8 = 2;
+const SEED_ACCOUNT_KEY: u8 = 3;
+const SEED_ACCOUNT_DATA: u8 = 4;
+
+/// Seed of this program's CPI signer PDA (quresis-core's `HOOK_CALLER_SEED`)
+pub const HOOK_CALLER_SEED: &[u8] = quresis::constants::HOOK_CALLER_SEED;
+
+/// Seed of quresis-core's approved hook registry (`APPROVED_HOOK_SEED`)
+pub const APPROVED_HOOK_SEED: &[u8] = quresis::constants::APPROVED_HOOK_SEED;
+
+/// Seed of quresis-core's per-mint velocity windows (`VELOCITY_SEED`)
+pub const VELOCITY_SEED: &[u8] = quresis::constants::VELOCITY_SEED;
+
+/// Seed of quresis-core's per-mint threshold overrides (`MINT_POLICY_SEED`)
+pub const MINT_POLICY_SEED: &[u8] = quresis::constants::MINT_POLICY_SEED;
+
+/// Token-2022 program, the only CPI caller of `execute` in a real transfer
+pub const TOKEN_2022_PROGRAM_ID: Pubkey =
+    anchor_lang::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Token-2022 account layout: 165-byte SPL Token base (mint at 0..32), then
+/// the account type byte, then TLV extensions (u16 type, u16 length, value).
+const TOKEN_ACCOUNT_TYPE_OFFSET: usize = 165;
+/// `AccountType::Account`
+const TOKEN_ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+/// `ExtensionType::TransferHookAccount` (value: `transferring: bool`)
+const TRANSFER_HOOK_ACCOUNT_EXTENSION: u16 = 15;
+
+/// SPL Token mint base layout: mint_authority (COption<Pubkey>, 36 bytes),
+/// supply (u64), then decimals and is_initialized.
+const MINT_DECIMALS_OFFSET: usize = 44;
+const MINT_IS_INITIALIZED_OFFSET: usize = 45;
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+/// Write the ExtraAccountMetaList TLV: the Quantum Guard entries in the order
+/// `execute` expects, then the issuer's `policy_metas`.
+///
+/// The SPL runtime parses the PDA data as:
+///   [0..8]   [u8; 8] = SPL `Execute` instruction discriminator (TLV type)
+///   [8..12]  u32 LE  = byte length of the value that follows
+///   [12..16] u32 LE  = number of entries
+///   [16..N]  []ExtraAccountMeta = tightly packed entries
+///
+/// Each ExtraAccountMeta entry = 35 bytes:
+///   [0]      u8           = discriminator (0 = literal, 128 + i = PDA of account i)
+///   [1..33]  [u8; 32]     = address_config (pubkey or packed seeds)
+///   [33]     bool         = is_signer
+///   [34]     bool         = is_writable
+///
+/// Entries are resolved in order, so a PDA entry may only refer to
+/// accounts at lower indices (quresis-core comes before the identity).
+fn write_extra_account_metas(
+    data: &mut [u8],
+    mint: &Pubkey,
+    policy_metas: &[ExtraAccountMetaEntry],
+) -> Result<()> {
+    // Resolve hook_config PDA address (to store as literal)
+    let (hook_config_pda, _) = Pubkey::find_program_address(
+        &[b"quresis_hook", mint.as_ref()],
+        &crate::ID,
+    );
+
+    // Mint-independent PDAs for authorized CPIs into quresis-core
+    let (hook_signer_pda, _) = Pubkey::find_program_address(&[HOOK_CALLER_SEED], &crate::ID);
+    let (approved_hook_pda, _) = Pubkey::find_program_address(
+        &[APPROVED_HOOK_SEED, crate::ID.as_ref()],
+        &quresis::ID,
+    );
+
+    let num_entries = GUARD_EXTRA_METAS + policy_metas.len();
+    let value_len = (4 + num_entries * EXTRA_ACCOUNT_META_ENTRY_SIZE) as u32;
+
+    // Write TLV header
+    data[0..8].copy_from_slice(&SPL_EXECUTE_DISCRIMINATOR);
+    data[8..12].copy_from_slice(&value_len.to_le_bytes());
+    data[12..16].copy_from_slice(&(num_entries as u32).to_le_bytes());
+
+    let mut entries = data[16..].chunks_exact_mut(EXTRA_ACCOUNT_META_ENTRY_SIZE);
+    let mut next_entry = || entries.next().ok_or(ProgramError::AccountDataTooSmall);
+
+    // ── Entry [0]: hook_config PDA (literal address, writable) ───────────
+    write_literal_meta(next_entry()?, &hook_config_pda, true);
+
+    // ── Entry [1]: quresis-core program ID (literal, read-only) ─────────
+    write_literal_meta(next_entry()?, &quresis::ID, false);
+
+    // ── Entry [2]: sender_identity [b"quresis_id", source_owner] @ quresis-core
+    // Writable: receives the rent of a consumed TransferAuthorization.
+    write_external_pda_meta(
+        next_entry()?,
+        QURESIS_PROGRAM_ACCOUNT_INDEX,
+        b"quresis_id",
+        &[SOURCE_OWNER_ACCOUNT_INDEX],
+        true,
+    );
+
+    // ── Entry [3]: transfer_authorization [b"quresis_auth", identity, mint] @ quresis-core
+    // Writable: closed when a HardEnforce receipt is consumed.
+    write_external_pda_meta(
+        next_entry()?,
+        QURESIS_PROGRAM_ACCOUNT_INDEX,
+        b"quresis_auth",
+        &[SENDER_IDENTITY_ACCOUNT_INDEX, MINT_ACCOUNT_INDEX],
+        true,
+    );
+
+    // ── Entry [4]: Instructions sysvar (literal, read-only) ──────────────
+    write_literal_meta(next_entry()?, &ix_sysvar::ID, false);
+
+    // ── Entry [5]: hook_signer PDA (literal; signed via invoke_signed) ───
+    // Writable: pays for new velocity states.
+    write_literal_meta(next_entry()?, &hook_signer_pda, true);
+
+    // ── Entry [6]: approved_hook registry entry (literal, read-only) ─────
+    write_literal_meta(next_entry()?, &approved_hook_pda, false);
+
+    // ── Entry [7]: velocity_state [b"quresis_velocity", identity, mint] @ quresis-core
+    // Writable: record_transfer creates and updates it via CPI.
+    write_external_pda_meta(
+        next_entry()?,
+        QURESIS_PROGRAM_ACCOUNT_INDEX,
+        VELOCITY_SEED,
+        &[SENDER_IDENTITY_ACCOUNT_INDEX, MINT_ACCOUNT_INDEX],
+        true,
+    );
+
+    // ── Entry [8]: System program (literal, read-only) ───────────────────
+    write_literal_meta(next_entry()?, &anchor_lang::system_program::ID, false);
+
+    // ── Entry [9]: mint_policy [b"quresis_mint_policy", identity, mint] @ quresis-core
+    write_external_pda_meta(
+        next_entry()?,
+        QURESIS_PROGRAM_ACCOUNT_INDEX,
+        MINT_POLICY_SEED,
+        &[SENDER_IDENTITY_ACCOUNT_INDEX, MINT_ACCOUNT_INDEX],
+        false,
+    );
+
+    // ── Entries [10..]: issuer policy accounts (remaining accounts of `execute`)
+    for meta in policy_metas {
+        let entry = next_entry()?;
+        entry[0] = meta.discriminator;
+        entry[1..33].copy_from_slice(&meta.address_config);
+        entry[33] = meta.is_signer as u8;
+        entry[34] = meta.is_writable as u8;
+    }
+
+    // Clear leftovers of a longer previous list
+    entries.into_remainder().fill(0);
+    Ok(())
+}
+
+/// Whether the standard resolver can resolve `meta` as `execute` account
+/// `account_index`: every account it refers to must come before it.
+fn is_resolvable_meta(meta: &ExtraAccountMetaEntry, account_index: usize) -> bool {
+    match meta.discriminator {
+        0 => true,
+        OWN_PDA_META => seeds_resolvable(&meta.address_config, account_index),
+        d if d >= EXTERNAL_PDA_META_BASE => {
+            ((d - EXTERNAL_PDA_META_BASE) as usize) < account_index
+                && seeds_resolvable(&meta.address_config, account_index)
+        }
+        _ => false,
+    }
+}
+
+/// Walk packed seeds; account key / data seeds must refer to earlier accounts
+fn seeds_resolvable(config: &[u8; 32], account_index: usize) -> bool {
+    let mut offset = 0;
+    let mut seeds = 0;
+    while offset < config.len() && config[offset] != 0 {
+        let rest = &config[offset + 1..];
+        let (consumed, referenced) = match config[offset] {
+            SEED_LITERAL => match rest.first() {
+                Some(&len) => (2 + len as usize, None),
+                None => return false,
+            },
+            SEED_INSTRUCTION_DATA => (3, None),
+            SEED_ACCOUNT_KEY => (2, rest.first().copied()),
+            SEED_ACCOUNT_DATA => (4, rest.first().copied()),
+            _ => return false,
+        };
+        if offset + consumed > config.len() {
+            return false;
+        }
+        if referenced.is_some_and(|index| index as usize >= account_index) {
+            return false;
+        }
+        offset += consumed;
+        seeds += 1;
+    }
+    seeds > 0
+}
+
+/// Write a literal-address ExtraAccountMeta
+fn write_literal_meta(entry: &mut [u8], address: &Pubkey, is_writable: bool) {
+    entry[0] = 0;
+    entry[1..33].copy_from_slice(address.as_ref());
+    entry[33] = 0; // is_signer = false
+    entry[34] = is_writable as u8;
+}
+
+/// Write an ExtraAccountMeta for a PDA of the program at `program_index`,
+/// with seeds = `literal` followed by the keys of `account_indices`
+fn write_external_pda_meta(
+    entry: &mut [u8],
+    program_index: u8,
+    literal: &[u8],
+    account_indices: &[u8],
+    is_writable: bool,
+) {
+    entry.fill(0);
+    entry[0] = EXTERNAL_PDA_META_BASE + program_index;
+
+    let config = &mut entry[1..33];
+    config[0] = SEED_LITERAL;
+    config[1] = literal.len() as u8;
+    config[2..2 + literal.len()].copy_from_slice(literal);
+    let mut offset = 2 + literal.len();
+    for &index in account_indices {
+        config[offset] = SEED_ACCOUNT_KEY;
+        config[offset + 1] = index;
+        offset += 2;
+    }
+
+    entry[33] = 0; // is_signer = false
+    entry[34] = is_writable as u8;
+}
+
+/// Require a Token-2022 account of `mint` whose `TransferHookAccount`
+/// extension has `transferring` set, i.e. one that is mid-transfer.
+fn require_transferring(account: &AccountInfo, mint: &Pubkey) -> Result<()> {
+    require_keys_eq!(
+        *account.owner,
+        TOKEN_2022_PROGRAM_ID,
+        QuresisHookError::InvalidTokenAccount
+    );
+
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() > TOKEN_ACCOUNT_TYPE_OFFSET
+            && data[0..32] == mint.to_bytes()
+            && data[TOKEN_ACCOUNT_TYPE_OFFSET] == TOKEN_ACCOUNT_TYPE_ACCOUNT,
+        QuresisHookError::InvalidTokenAccount
+    );
+
+    let mut offset = TOKEN_ACCOUNT_TYPE_OFFSET + 1;
+    while offset + 4 <= data.len() {
+        let extension_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let value = offset + 4;
+
+        if extension_type == TRANSFER_HOOK_ACCOUNT_EXTENSION {
+            require!(
+                length >= 1 && data.get(value) == Some(&1),
+                QuresisHookError::NotTransferring
+            );
+            return Ok(());
+        }
+        // Type 0 (Uninitialized) marks the end of the extensions
+        if extension_type == 0 {
+            break;
+        }
+        offset = value + length;
+    }
+
+    err!(QuresisHookError::NotTransferring)
+}
+
+/// Move the rent of a new `VelocityState` from `hook_config` to the hook's
+/// signer PDA, which pays for it in `quresis::record_transfer`. Returns
+/// `false`, moving nothing, when the mint's HookConfig does not hold that
+/// much above its own rent-exempt minimum.
+///
+/// Lamports anyone sent to the signer are not counted: they stay where they
+/// are, so the signer's balance is the same before and after the creation.
+fn fund_velocity_state<'info>(
+    hook_config: &Account<'info, HookConfig>,
+    hook_signer: &AccountInfo<'info>,
+    velocity_state: &AccountInfo<'info>,
+) -> Result<bool> {
+    let rent = Rent::get()?;
+    let needed = rent
+        .minimum_balance(VELOCITY_STATE_LEN)
+        .saturating_sub(velocity_state.lamports());
+    if needed == 0 {
+        return Ok(true);
+    }
+
+    let hook_config = hook_config.to_account_info();
+    let spare = hook_config
+        .lamports()
+        .saturating_sub(rent.minimum_balance(hook_config.data_len()));
+    if spare < needed {
+        msg!("   HookConfig needs {} more lamports for a VelocityState", needed - spare);
+        return Ok(false);
+    }
+
+    hook_config.sub_lamports(needed)?;
+    hook_signer.add_lamports(needed)?;
+    Ok(true)
+}
+
+/// Decimals of an initialized Token-2022 mint, read from its base layout.
+fn mint_decimals(mint: &AccountInfo) -> Result<u8> {
+    require_keys_eq!(*mint.owner, TOKEN_2022_PROGRAM_ID, QuresisHookError::InvalidMint);
+
+    let data = mint.try_borrow_data()?;
+    require!(
+        data.len() > MINT_IS_INITIALIZED_OFFSET && data[MINT_IS_INITIALIZED_OFFSET] == 1,
+        QuresisHookError::InvalidMint
+    );
+
+    Ok(data[MINT_DECIMALS_OFFSET])
+}
+
+/// Whether the sender's TransferAuthorization PDA holds a receipt that has
+/// not been spent yet. Consumed receipts stay until someone closes them, and
+/// receipts in the layout without a payer can no longer be consumed.
+fn has_unspent_authorization(receipt: &AccountInfo) -> Result<bool> {
+    if receipt.data_is_empty() || receipt.data_len() == quresis::TransferAuthorization::LEGACY_LEN {
+        return Ok(false);
+    }
+    require_keys_eq!(*receipt.owner, quresis::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let receipt = quresis::TransferAuthorization::try_deserialize(&mut &receipt.try_borrow_data()?[..])?;
+    Ok(!receipt.consumed)
+}
+
+/// Same-transaction authorization: true when the instruction right before
+/// the transfer is a successful `quresis::verify_signature` (no receipt) of
+/// the sender's identity over the `QURESIS_TRANSFER_V1` message for this
+/// mint, destination and amount, signed at the sequence just consumed. Core
+/// only accepts that call with the identity authority's signature.
+///
+/// The transfer must be a top-level Token-2022 instruction (or a direct call
+/// of this hook, which moves no tokens), so one verification can never cover
+/// two transfers made by CPI from the same instruction.
+fn authorized_in_transaction(accounts: &Execute, amount: u64, sequence: u64) -> Result<bool> {
+    let sysvar = &accounts.instructions_sysvar;
+    let current_index = ix_sysvar::load_current_index_checked(sysvar)?;
+    let current = ix_sysvar::load_instruction_at_checked(current_index as usize, sysvar)?;
+    if current.program_id != TOKEN_2022_PROGRAM_ID && current.program_id != crate::ID {
+        return Ok(false);
+    }
+
+    let Some(previous_index) = current_index.checked_sub(1) else {
+        return Ok(false);
+    };
+    let previous = ix_sysvar::load_instruction_at_checked(previous_index as usize, sysvar)?;
+    let disc = quresis::instruction::VerifySignature::DISCRIMINATOR;
+    if previous.program_id != quresis::ID || !previous.data.starts_with(disc) {
+        return Ok(false);
+    }
+
+    // verify_signature(message: Vec<u8>, signature: Vec<u8>, authorization: Option<_>)
+    let args = quresis::instruction::VerifySignature::try_from_slice(&previous.data[disc.len()..])
+        .map_err(|_| QuresisHookError::TransferAuthorizationMismatch)?;
+    let identity = previous.accounts.first().map(|meta| meta.pubkey);
+    require!(
+        args.authorization.is_none() && identity == Some(accounts.sender_identity.key()),
+        QuresisHookError::TransferAuthorizationMismatch
+    );
+
+    // domain || mint || destination || amount_cap || expires_at_slot || sequence
+    let message = args.message;
+    let domain = quresis::constants::TRANSFER_AUTH_DOMAIN;
+    require!(
+        message.len() == domain.len() + 32 + 32 + 8 + 8 + 8 && message.starts_with(domain),
+        QuresisHookError::TransferAuthorizationMismatch
+    );
+    let fields = &message[domain.len()..];
+    let read_u64 = |at: usize| u64::from_le_bytes(fields[at..at + 8].try_into().unwrap());
+
+    require!(
+        fields[0..32] == accounts.mint.key().to_bytes()
+            && fields[32..64] == accounts.destination_token_account.key().to_bytes()
+            && amount <= read_u64(64)
+            && Clock::get()?.slot <= read_u64(72)
+            && read_u64(80).checked_add(1) == Some(sequence),
+        QuresisHookError::TransferAuthorizationMismatch
+    );
+
+    Ok(true)
+}
@@ -1,4 +1,13 @@
 use anchor_lang::prelude::*;
+use quresis_common::{
+    quantum_identity_version, FORWARD_AUTHORITY_OFFSET, QUANTUM_IDENTITY_VERSION,
+    QUANTUM_IDENTITY_VERSION_BORSH,
+};
+use std::cell::{Ref, RefMut};
+
+extern crate alloc;
+
+pub mod ml_dsa;
 
 declare_id!("7SwY7dD2rQTvWs8KUB1xsy3GuUbKBoJdcPvx8kGiuojv");
 
@@ -11,18 +20,62 @@ pub mod constants {
     pub const ML_DSA_44_SIG_SIZE: usize = 2420;
     /// ML-DSA-65 Public Key Size (1952 bytes)
     pub const ML_DSA_65_PUBKEY_SIZE: usize = 1952;
-    /// ML-DSA-65 Signature Size (3293 bytes)
-    pub const ML_DSA_65_SIG_SIZE: usize = 3293;
+    /// ML-DSA-65 Signature Size (3309 bytes)
+    pub const ML_DSA_65_SIG_SIZE: usize = 3309;
     /// Maximum supported key size (with buffer)
     pub const MAX_PQC_PUBKEY_SIZE: usize = 2048;
+    /// Mock key size accepted only by `mock-pqc` test builds
+    /// (fits in a single transaction alongside the other arguments)
+    pub const MOCK_PQC_PUBKEY_SIZE: usize = 32;
     /// PDA Seed Prefix
     pub const SEED_PREFIX: &[u8] = b"quresis_id";
-    /// Default threshold amount in lamports (100 SOL = 100 * 10^9)
+    /// Signature Buffer PDA Seed Prefix
+    pub const SIG_BUFFER_SEED: &[u8] = b"quresis_sig";
+    /// Domain prefix of the proof-of-possession message signed by a new key
+    pub const KEY_POSSESSION_DOMAIN: &[u8] = b"QURESIS_KEY_POSSESSION_V1:";
+    /// Domain prefix of the key rotation message signed by the old key
+    pub const KEY_ROTATION_DOMAIN: &[u8] = b"QURESIS_KEY_ROTATION_V1:";
+    /// Domain prefix of the message a key signs to revoke its identity
+    pub const CLOSE_IDENTITY_DOMAIN: &[u8] = b"QURESIS_CLOSE_IDENTITY_V1:";
+    /// Domain prefix of the message a key signs to move its identity to a new wallet
+    pub const MIGRATE_AUTHORITY_DOMAIN: &[u8] = b"QURESIS_MIGRATE_AUTHORITY_V1:";
+    /// Domain prefix of the message a key signs to freeze its identity
+    pub const FREEZE_DOMAIN: &[u8] = b"QURESIS_FREEZE_V1:";
+    /// Domain prefix of the message a key signs to unfreeze its identity
+    pub const UNFREEZE_DOMAIN: &[u8] = b"QURESIS_UNFREEZE_V1:";
+    /// Domain prefix of the message a key signs to raise its threshold
+    pub const THRESHOLD_DOMAIN: &[u8] = b"QURESIS_THRESHOLD_V1:";
+    /// Domain prefix of the message a key signs to loosen a mint policy
+    pub const MINT_POLICY_DOMAIN: &[u8] = b"QURESIS_MINT_POLICY_V1:";
+    /// Domain prefix of the message a key signs to delete a mint policy
+    pub const MINT_POLICY_DELETE_DOMAIN: &[u8] = b"QURESIS_MINT_POLICY_DELETE_V1:";
+    /// Slots a revoked identity must wait before `reopen_identity` (~1 day at 400ms/slot)
+    pub const REREGISTRATION_COOLDOWN_SLOTS: u64 = 216_000;
+    /// Verification Session PDA Seed Prefix
+    pub const VERIFY_SESSION_SEED: &[u8] = b"quresis_verify";
+    /// Slots a verification session stays usable (~10 minutes at 400ms/slot)
+    pub const VERIFY_SESSION_TTL_SLOTS: u64 = 1_500;
+    /// Transfer Authorization PDA Seed Prefix
+    pub const TRANSFER_AUTH_SEED: &[u8] = b"quresis_auth";
+    /// Velocity State PDA Seed Prefix (one per identity and mint)
+    pub const VELOCITY_SEED: &[u8] = b"quresis_velocity";
+    /// Mint Policy PDA Seed Prefix (one per identity and mint)
+    pub const MINT_POLICY_SEED: &[u8] = b"quresis_mint_policy";
+    /// Domain prefix of the message authorizing a high-value transfer
+    pub const TRANSFER_AUTH_DOMAIN: &[u8] = b"QURESIS_TRANSFER_V1:";
+    /// Approved Hook registry PDA Seed Prefix
+    pub const APPROVED_HOOK_SEED: &[u8] = b"quresis_approved_hook";
+    /// Seed of the signer PDA an approved hook program signs its CPIs with
+    pub const HOOK_CALLER_SEED: &[u8] = b"quresis_caller";
+    /// Decimals of threshold amounts: 10^9 units = one whole token of any
+    /// mint (lamports for SOL). The hook normalizes by the mint's decimals.
+    pub use quresis_common::THRESHOLD_DECIMALS;
+    /// Default threshold amount (100 tokens = 100 * 10^9 units)
     pub const DEFAULT_THRESHOLD: u64 = 100_000_000_000;
-    /// Minimum threshold amount in lamports (1 SOL = 10^9)
+    /// Minimum threshold amount (1 token = 10^9 units)
     /// Prevents setting threshold too low which would require PQC for every transfer
     pub const MIN_THRESHOLD: u64 = 1_000_000_000;
-    /// Maximum threshold amount in lamports (1,000,000 SOL)
+    /// Maximum threshold amount (10^9 tokens)
     /// Prevents setting threshold so high that PQC is effectively disabled
     pub const MAX_THRESHOLD: u64 = 1_000_000_000_000_000_000;
 }
@@ -35,16 +88,19 @@ pub mod quresis {
 
     /// Register a new Quantum Identity
     /// Links a Solana wallet (Ed25519) with a Post-Quantum public key (ML-DSA)
+    ///
+    /// `possession_signature` is the new key's signature over
+    /// `KEY_POSSESSION_DOMAIN || program_id || authority` (inline, or in a
+    /// finalized `signature_buffer`), so nobody can bind a key they do not hold.
     pub fn register_identity(
         ctx: Context<RegisterIdentity>,
         pqc_public_key: Vec<u8>,
         threshold_amount: Option<u64>,
+        possession_signature: Vec<u8>,
     ) -> Result<()> {
         // Validate key length
         require!(
-            pqc_public_key.len() == ML_DSA_44_PUBKEY_SIZE
-                || pqc_public_key.len() == ML_DSA_65_PUBKEY_SIZE
-                || pqc_public_key.len() == 32, // Allow 32-byte mock key for testing/demo (Solana MTU limit is 1232 bytes)
+            is_supported_key_length(pqc_public_key.len()),
             QuresisError::InvalidKeyLength
         );
 
@@ -55,71 +111,236 @@ pub mod quresis {
             QuresisError::InvalidThreshold
         );
 
-        let identity = &mut ctx.accounts.identity;
+        let possession_signature =
+            resolve_signature(&possession_signature, &ctx.accounts.signature_buffer)?;
+        require_key_possession(
+            &pqc_public_key,
+            &ctx.accounts.authority.key(),
+            possession_signature,
+        )?;
+
+        let key_len = pqc_public_key.len() as u16;
         let clock = Clock::get()?;
 
-        identity.authority = ctx.accounts.authority.key();
-        identity.pqc_public_key = pqc_public_key;
-        identity.bump = ctx.bumps.identity;
-        identity.sequence = 0;
-        identity.last_active_slot = clock.slot;
-        identity.created_at = clock.unix_timestamp;
-        identity.is_frozen = false;
-        identity.threshold_amount = threshold;
-        identity.key_version = 1;
+        identity_key_mut(&ctx.accounts.identity, key_len)?.copy_from_slice(&pqc_public_key);
 
-        // Initialize Velocity tracking
-        identity.current_window_start = clock.unix_timestamp;
-        identity.current_window_amount = 0;
+        let mut identity = ctx.accounts.identity.load_init()?;
+        init_identity(
+            &mut identity,
+            ctx.accounts.authority.key(),
+            ctx.bumps.identity,
+            key_len,
+            threshold,
+            &clock,
+        );
+        // The whole key arrived inline, so the identity is usable immediately
+        identity.key_written_len = key_len;
+        identity.is_sealed = 1;
 
         emit!(IdentityRegistered {
             authority: identity.authority,
-            key_size: identity.pqc_public_key.len() as u16,
+            key_size: key_len,
             threshold: identity.threshold_amount,
             slot: clock.slot,
         });
 
         msg!("✅ Quantum Identity Registered for: {}", identity.authority);
-        msg!("   PQC Key Size: {} bytes", identity.pqc_public_key.len());
-        msg!("   Threshold: {} lamports", identity.threshold_amount);
+        msg!("   PQC Key Size: {} bytes", key_len);
+        msg!("   Threshold: {} units (9 decimals)", identity.threshold_amount);
+
+        Ok(())
+    }
+
+    /// Start a staged registration for keys too large for one transaction.
+    /// Allocates the QuantumIdentity PDA with room for a `key_len`-byte key;
+    /// the key is then uploaded with `write_key_chunk` and the identity
+    /// becomes usable once `seal_identity` succeeds.
+    pub fn begin_registration(
+        ctx: Context<BeginRegistration>,
+        key_len: u32,
+        threshold_amount: Option<u64>,
+    ) -> Result<()> {
+        require!(
+            is_supported_key_length(key_len as usize),
+            QuresisError::InvalidKeyLength
+        );
+
+        let threshold = threshold_amount.unwrap_or(DEFAULT_THRESHOLD);
+        require!(
+            (MIN_THRESHOLD..=MAX_THRESHOLD).contains(&threshold),
+            QuresisError::InvalidThreshold
+        );
+
+        let mut identity = ctx.accounts.identity.load_init()?;
+        let clock = Clock::get()?;
+
+        // The key region after the header is already zeroed by the allocation
+        init_identity(
+            &mut identity,
+            ctx.accounts.authority.key(),
+            ctx.bumps.identity,
+            key_len as u16,
+            threshold,
+            &clock,
+        );
+        identity.key_written_len = 0;
+        identity.is_sealed = 0;
+
+        msg!("📦 Staged registration started for: {}", identity.authority);
+        msg!("   PQC Key Size: {} bytes", key_len);
+
+        Ok(())
+    }
+
+    /// Write a chunk of the public key of an unsealed identity at `offset`.
+    /// Same rules as `write_chunk`: chunks may be rewritten but never leave a gap.
+    pub fn write_key_chunk(
+        ctx: Context<ManageIdentity>,
+        offset: u32,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let (key_len, key_written_len) = {
+            let identity = ctx.accounts.identity.load()?;
+            require!(identity.is_sealed == 0, QuresisError::IdentityAlreadySealed);
+            (identity.key_len, identity.key_written_len)
+        };
+
+        let start = offset as usize;
+        let end = start
+            .checked_add(data.len())
+            .ok_or(QuresisError::ChunkOutOfBounds)?;
+        require!(
+            start <= key_written_len as usize && end <= key_len as usize,
+            QuresisError::ChunkOutOfBounds
+        );
+
+        identity_key_mut(&ctx.accounts.identity, key_len)?[start..end].copy_from_slice(&data);
+
+        let mut identity = ctx.accounts.identity.load_mut()?;
+        identity.key_written_len = key_written_len.max(end as u16);
+
+        msg!(
+            "📦 PQC Key Upload: {}/{} bytes",
+            identity.key_written_len,
+            key_len
+        );
+
+        Ok(())
+    }
+
+    /// Complete a staged registration. Fails unless every key byte was written
+    /// and `possession_signature` proves control of the uploaded key (same
+    /// message as `register_identity`).
+    pub fn seal_identity(
+        ctx: Context<SealIdentity>,
+        possession_signature: Vec<u8>,
+    ) -> Result<()> {
+        let possession_signature =
+            resolve_signature(&possession_signature, &ctx.accounts.signature_buffer)?;
+        {
+            let identity = ctx.accounts.identity.load()?;
+            require!(identity.is_sealed == 0, QuresisError::IdentityAlreadySealed);
+            require!(
+                identity.key_written_len == identity.key_len
+                    && is_supported_key_length(identity.key_len as usize),
+                QuresisError::KeyIncomplete
+            );
+            require_key_possession(
+                &identity_key(&ctx.accounts.identity, identity.key_len)?,
+                &identity.authority,
+                possession_signature,
+            )?;
+        }
+
+        let mut identity = ctx.accounts.identity.load_mut()?;
+        let clock = Clock::get()?;
+        identity.is_sealed = 1;
+        // Completes a re-registration (`reopen_identity`) too
+        identity.is_revoked = 0;
+        identity.last_active_slot = clock.slot;
+
+        emit!(IdentityRegistered {
+            authority: identity.authority,
+            key_size: identity.key_len,
+            threshold: identity.threshold_amount,
+            slot: clock.slot,
+        });
+
+        msg!("🔏 Quantum Identity Sealed for: {}", identity.authority);
+        msg!("   PQC Key Size: {} bytes", identity.key_len);
+        msg!("   Threshold: {} units (9 decimals)", identity.threshold_amount);
 
         Ok(())
     }
 
     /// Rotate the quantum key (requires signature from OLD key)
     /// Critical for long-term security maintenance
+    ///
+    /// The old key must sign `KEY_ROTATION_DOMAIN || sequence (u64 LE) || new_key`
+    /// (the SDK's `createRotationMessage`). The message is rebuilt on-chain,
+    /// so a signature only authorizes this exact key at this sequence.
+    ///
+    /// The new key must also prove possession with `new_key_signature`, as in
+    /// `register_identity`.
+    ///
+    /// Like the signatures, the new key can be passed inline or, when it does
+    /// not fit in a transaction, staged in a finalized `new_key_buffer`.
     pub fn rotate_key(
         ctx: Context<RotateKey>,
         new_pqc_public_key: Vec<u8>,
         old_key_signature: Vec<u8>,
-        signature_message: Vec<u8>,
+        new_key_signature: Vec<u8>,
     ) -> Result<()> {
-        let identity = &mut ctx.accounts.identity;
+        let old_key_signature =
+            resolve_signature(&old_key_signature, &ctx.accounts.signature_buffer)?;
+        let new_key_signature =
+            resolve_signature(&new_key_signature, &ctx.accounts.new_key_signature_buffer)?;
+        let new_pqc_public_key = resolve_new_key(
+            new_pqc_public_key,
+            &ctx.accounts.new_key_buffer,
+            &ctx.accounts.authority.key(),
+        )?;
 
         // Validate new key length
         require!(
-            new_pqc_public_key.len() == ML_DSA_44_PUBKEY_SIZE
-                || new_pqc_public_key.len() == ML_DSA_65_PUBKEY_SIZE
-                || new_pqc_public_key.len() == 32,
+            is_supported_key_length(new_pqc_public_key.len()),
             QuresisError::InvalidKeyLength
         );
 
-        require!(!identity.is_frozen, QuresisError::IdentityFrozen);
-
-        // Verify signature using OLD key (Post-Quantum 2FA)
-        // This ensures the rotation is authorized by the current key holder
-        let is_valid = mock_pqc_verify(
-            &identity.pqc_public_key,
-            &signature_message,
-            &old_key_signature,
-        );
-        require!(is_valid, QuresisError::InvalidQuantumSignature);
+        {
+            let identity = ctx.accounts.identity.load()?;
+            require!(identity.is_sealed != 0, QuresisError::IdentityNotSealed);
+            require!(identity.is_frozen == 0, QuresisError::IdentityFrozen);
+
+            // Verify signature using OLD key (Post-Quantum 2FA)
+            // This ensures the rotation is authorized by the current key holder
+            let signature_message = rotation_message(identity.sequence, &new_pqc_public_key);
+            require_valid_pqc_signature(
+                &identity_key(&ctx.accounts.identity, identity.key_len)?,
+                &signature_message,
+                old_key_signature,
+            )?;
+            require_key_possession(&new_pqc_public_key, &identity.authority, new_key_signature)?;
+        }
 
+        // Resize only now: shrinking first would cut off the old key
+        let new_key_len = new_pqc_public_key.len() as u16;
+        resize_identity(
+            &ctx.accounts.identity,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            QuantumIdentity::space(new_pqc_public_key.len()),
+        )?;
+        identity_key_mut(&ctx.accounts.identity, new_key_len)?
+            .copy_from_slice(&new_pqc_public_key);
+
+        let mut identity = ctx.accounts.identity.load_mut()?;
         let old_version = identity.key_version;
         let clock = Clock::get()?;
 
         // Update to new key
-        identity.pqc_public_key = new_pqc_public_key;
+        identity.key_len = new_key_len;
+        identity.key_written_len = new_key_len;
         identity.key_version = identity.key_version.checked_add(1).unwrap_or(u16::MAX);
         identity.last_active_slot = clock.slot;
         identity.sequence = identity.sequence.checked_add(1).unwrap_or(u64::MAX);
@@ -128,7 +349,7 @@ pub mod quresis {
             authority: identity.authority,
             old_version,
             new_version: identity.key_version,
-            new_key_size: identity.pqc_public_key.len() as u16,
+            new_key_size: new_key_len,
             slot: clock.slot,
         });
 
@@ -140,41 +361,151 @@ pub mod quresis {
 
     /// Verify a quantum signature
     /// Called by Transfer Hook or external programs via CPI
+    ///
+    /// The signature is either passed inline in `signature`, or (for real
+    /// ML-DSA signatures, which exceed the transaction MTU) staged in a
+    /// finalized `SignatureBuffer` with `signature` left empty.
+    ///
+    /// Alternatively, a `VerificationSession` that already reached
+    /// `Verified` for this identity and message can be passed (with
+    /// `signature` empty); it is consumed and cannot be reused.
+    ///
+    /// Anti-replay: `message` must end with the identity's current
+    /// `sequence` (u64 LE), which is advanced on success. Messages in the
+    /// domains of identity actions (`unfreeze`, `close_identity`, ...) are
+    /// refused, so a signature seen in flight for one of those cannot be
+    /// spent here first to burn its sequence.
+    ///
+    /// With `authorization` set, `message` must be exactly the transfer
+    /// message for it (the SDK's `createTransferMessage`), and a single-use
+    /// `TransferAuthorization` receipt is minted for the transfer hook. A
+    /// consumed or expired receipt for the same mint has to be closed first.
+    /// Without it, a transfer message is only accepted when the identity's
+    /// `authority` signs, so a pending authorization or a verification taken
+    /// out of its transfer transaction cannot be replayed to burn the sequence.
     pub fn verify_signature(
         ctx: Context<VerifySignature>,
         message: Vec<u8>,
         signature: Vec<u8>,
+        authorization: Option<TransferAuthorizationParams>,
     ) -> Result<()> {
-        let identity = &ctx.accounts.identity;
-
-        require!(!identity.is_frozen, QuresisError::IdentityFrozen);
-
-        // --- NATIVE PQC SYSCALL INTEGRATION ZONE ---
-        // Currently using Mock Verification (Development Phase)
-        // Will be replaced with: solana_program::pqc::verify_ml_dsa()
-        let is_valid = mock_pqc_verify(
-            &identity.pqc_public_key,
-            &message,
-            &signature,
-        );
+        let identity_key_address = ctx.accounts.identity.key();
+        {
+            let identity = ctx.accounts.identity.load()?;
+
+            require!(identity.is_sealed != 0, QuresisError::IdentityNotSealed);
+            require!(identity.is_frozen == 0, QuresisError::IdentityFrozen);
+            require_current_sequence(&message, identity.sequence)?;
+            require!(
+                !is_reserved_message(&message),
+                QuresisError::ReservedMessageDomain
+            );
+            require!(
+                authorization.is_some() == ctx.accounts.transfer_authorization.is_some(),
+                QuresisError::MissingTransferAuthorization
+            );
+            if authorization.is_none() && message.starts_with(TRANSFER_AUTH_DOMAIN) {
+                require!(
+                    ctx.accounts.authority.as_ref().map(|a| a.key()) == Some(identity.authority),
+                    QuresisError::TransferNeedsAuthority
+                );
+            }
+            if let Some(params) = &authorization {
+                require!(
+                    message == transfer_authorization_message(identity.sequence, params),
+                    QuresisError::TransferMessageMismatch
+                );
+            }
+
+            if let Some(session) = ctx.accounts.verification_session.as_mut() {
+                require!(
+                    signature.is_empty() && ctx.accounts.signature_buffer.is_none(),
+                    QuresisError::AmbiguousSignatureSource
+                );
+                consume_verification_session(
+                    session,
+                    identity_key_address,
+                    identity.key_version,
+                    &message,
+                )?;
+            } else {
+                let signature = resolve_signature(&signature, &ctx.accounts.signature_buffer)?;
+
+                // --- NATIVE PQC SYSCALL INTEGRATION ZONE ---
+                // FIPS 204 ML-DSA verification in program space (see `ml_dsa`).
+                // Can be swapped for a native ML-DSA syscall once one ships.
+                require_valid_pqc_signature(
+                    &identity_key(&ctx.accounts.identity, identity.key_len)?,
+                    &message,
+                    signature,
+                )?;
+            }
+        }
 
-        require!(is_valid, QuresisError::InvalidQuantumSignature);
+        let mut identity = ctx.accounts.identity.load_mut()?;
+        let sequence = identity.sequence;
+        identity.sequence = sequence
+            .checked_add(1)
+            .ok_or(QuresisError::SequenceMismatch)?;
 
+        let slot = Clock::get()?.slot;
         emit!(SignatureVerified {
             authority: identity.authority,
             message_hash: hash_message(&message),
-            slot: Clock::get()?.slot,
+            sequence,
+            slot,
         });
 
-        msg!("✅ Quantum Signature Verified!");
+        msg!("✅ Quantum Signature Verified! (sequence {})", sequence);
+
+        if let (Some(params), Some(receipt)) =
+            (authorization, ctx.accounts.transfer_authorization.as_mut())
+        {
+            require!(
+                slot <= params.expires_at_slot,
+                QuresisError::AuthorizationExpired
+            );
+
+            receipt.identity = identity_key_address;
+            receipt.mint = params.mint;
+            receipt.destination = params.destination;
+            receipt.amount_cap = params.amount_cap;
+            receipt.expires_at_slot = params.expires_at_slot;
+            receipt.sequence = sequence;
+            receipt.payer = ctx.accounts.payer.as_ref().map(|p| p.key()).unwrap_or_default();
+            receipt.consumed = false;
+            receipt.bump = ctx.bumps.transfer_authorization.unwrap_or_default();
+
+            emit!(TransferAuthorized {
+                authority: identity.authority,
+                mint: params.mint,
+                destination: params.destination,
+                amount_cap: params.amount_cap,
+                expires_at_slot: params.expires_at_slot,
+                sequence,
+            });
+
+            msg!(
+                "🎫 Transfer Authorized: up to {} to {} (until slot {})",
+                params.amount_cap,
+                params.destination,
+                params.expires_at_slot
+            );
+        }
 
         Ok(())
     }
 
     /// Update the threshold amount for quantum signature requirement
+    ///
+    /// Lowering it only needs the authority. Raising it weakens protection,
+    /// so it also needs the PQC key's signature over `THRESHOLD_DOMAIN ||
+    /// authority || new_threshold (u64 LE) || sequence (u64 LE)` (the SDK's
+    /// `createThresholdMessage`), inline or in a finalized `signature_buffer`.
     pub fn update_threshold(
-        ctx: Context<ManageIdentity>,
+        ctx: Context<AuthorizeIdentityUpdate>,
         new_threshold: u64,
+        signature: Vec<u8>,
     ) -> Result<()> {
         // Validate new threshold
         require!(
@@ -182,9 +513,26 @@ pub mod quresis {
             QuresisError::InvalidThreshold
         );
 
-        let identity = &mut ctx.accounts.identity;
-        let old_threshold = identity.threshold_amount;
+        let (old_threshold, is_sealed) = {
+            let identity = ctx.accounts.identity.load()?;
+            (identity.threshold_amount, identity.is_sealed != 0)
+        };
+        require!(is_sealed, QuresisError::IdentityNotSealed);
+
+        let is_raise = new_threshold > old_threshold;
+        if is_raise {
+            require_identity_action_signature(
+                &ctx.accounts.identity,
+                THRESHOLD_DOMAIN,
+                &new_threshold.to_le_bytes(),
+                resolve_signature(&signature, &ctx.accounts.signature_buffer)?,
+            )?;
+        }
 
+        let mut identity = ctx.accounts.identity.load_mut()?;
+        if is_raise {
+            identity.sequence = identity.sequence.saturating_add(1);
+        }
         identity.threshold_amount = new_threshold;
         identity.last_active_slot = Clock::get()?.slot;
 
@@ -194,248 +542,2044 @@ pub mod quresis {
             new_threshold,
         });
 
-        msg!("📊 Threshold Updated: {} -> {} lamports", old_threshold, new_threshold);
+        msg!("📊 Threshold Updated: {} -> {} units", old_threshold, new_threshold);
 
         Ok(())
     }
 
-    /// Emergency freeze - locks the identity if key compromise is suspected
-    pub fn toggle_freeze(ctx: Context<ManageIdentity>) -> Result<()> {
-        let identity = &mut ctx.accounts.identity;
-        identity.is_frozen = !identity.is_frozen;
-        identity.last_active_slot = Clock::get()?.slot;
+    /// Give one mint its own threshold and 24h velocity limit, which the hook
+    /// uses instead of the identity's `threshold_amount` for that mint.
+    ///
+    /// Follows the `update_threshold` rules against the identity threshold
+    /// the mint falls back to today: tightening only needs the authority,
+    /// while raising either value also needs the PQC key's signature over
+    /// `MINT_POLICY_DOMAIN || authority || mint || threshold_amount (u64 LE)
+    /// || velocity_limit (u64 LE) || sequence (u64 LE)` (the SDK's
+    /// `createMintPolicyMessage`), inline or in a finalized `signature_buffer`.
+    pub fn create_mint_policy(
+        ctx: Context<CreateMintPolicy>,
+        threshold_amount: u64,
+        velocity_limit: u64,
+        signature: Vec<u8>,
+    ) -> Result<()> {
+        let mint = ctx.accounts.mint.key();
+        let identity_threshold = ctx.accounts.identity.load()?.threshold_amount;
+
+        authorize_mint_policy_change(
+            &ctx.accounts.identity,
+            &mint,
+            (identity_threshold, identity_threshold),
+            Some((threshold_amount, velocity_limit)),
+            resolve_signature(&signature, &ctx.accounts.signature_buffer)?,
+        )?;
+
+        {
+            let mut identity = ctx.accounts.identity.load_mut()?;
+            identity.mint_policy_count = identity
+                .mint_policy_count
+                .checked_add(1)
+                .ok_or(QuresisError::TooManyMintPolicies)?;
+        }
 
-        emit!(FreezeToggled {
-            authority: identity.authority,
-            is_frozen: identity.is_frozen,
-            slot: Clock::get()?.slot,
+        let mint_policy = &mut ctx.accounts.mint_policy;
+        mint_policy.identity = ctx.accounts.identity.key();
+        mint_policy.mint = mint;
+        mint_policy.threshold_amount = threshold_amount;
+        mint_policy.velocity_limit = velocity_limit;
+        mint_policy.bump = ctx.bumps.mint_policy;
+
+        emit!(MintPolicyChanged {
+            authority: ctx.accounts.authority.key(),
+            mint,
+            threshold_amount,
+            velocity_limit,
+            removed: false,
         });
 
         msg!(
-            "🔒 Identity Freeze State: {}",
-            if identity.is_frozen { "FROZEN" } else { "ACTIVE" }
+            "📊 Mint Policy Created: mint={}, threshold={} units, velocity_limit={} units",
+            mint,
+            threshold_amount,
+            velocity_limit
         );
 
         Ok(())
     }
 
-    /// Close and reclaim rent from an identity account
-    pub fn close_identity(_ctx: Context<CloseIdentity>) -> Result<()> {
-        msg!("🗑️ Identity Account Closed");
-        Ok(())
-    }
+    /// Change a mint policy's threshold and velocity limit. Raising either
+    /// value needs the PQC key's signature, as in `create_mint_policy`.
+    pub fn update_mint_policy(
+        ctx: Context<UpdateMintPolicy>,
+        threshold_amount: u64,
+        velocity_limit: u64,
+        signature: Vec<u8>,
+    ) -> Result<()> {
+        let mint_policy = &mut ctx.accounts.mint_policy;
+
+        authorize_mint_policy_change(
+            &ctx.accounts.identity,
+            &mint_policy.mint,
+            (mint_policy.threshold_amount, mint_policy.velocity_limit),
+            Some((threshold_amount, velocity_limit)),
+            resolve_signature(&signature, &ctx.accounts.signature_buffer)?,
+        )?;
+
+        mint_policy.threshold_amount = threshold_amount;
+        mint_policy.velocity_limit = velocity_limit;
+
+        emit!(MintPolicyChanged {
+            authority: ctx.accounts.authority.key(),
+            mint: mint_policy.mint,
+            threshold_amount,
+            velocity_limit,
+            removed: false,
+        });
 
-    /// Record a transfer to update the user's velocity window.
-    /// Typically called via CPI by the transfer hook.
-    pub fn record_transfer(ctx: Context<RecordTransfer>, amount: u64) -> Result<()> {
-        let identity = &mut ctx.accounts.identity;
-        require!(!identity.is_frozen, QuresisError::IdentityFrozen);
+        msg!(
+            "📊 Mint Policy Updated: mint={}, threshold={} units, velocity_limit={} units",
+            mint_policy.mint,
+            threshold_amount,
+            velocity_limit
+        );
 
-        let current_time = Clock::get()?.unix_timestamp;
-        let window_size: i64 = 24 * 60 * 60; // 24 hours 
+        Ok(())
+    }
 
-        // Check if we need to reset the sliding window
-        if current_time >= identity.current_window_start.saturating_add(window_size) {
-            identity.current_window_start = current_time;
-            identity.current_window_amount = 0;
+    /// Delete a mint policy and refund its rent; the mint falls back to the
+    /// identity's `threshold_amount`. If that is higher than either policy
+    /// value, this loosens protection and needs the PQC key's signature over
+    /// `MINT_POLICY_DELETE_DOMAIN || authority || mint || sequence (u64 LE)`
+    /// (the SDK's `createMintPolicyDeleteMessage`).
+    pub fn delete_mint_policy(ctx: Context<DeleteMintPolicy>, signature: Vec<u8>) -> Result<()> {
+        let mint_policy = &ctx.accounts.mint_policy;
+
+        authorize_mint_policy_change(
+            &ctx.accounts.identity,
+            &mint_policy.mint,
+            (mint_policy.threshold_amount, mint_policy.velocity_limit),
+            None,
+            resolve_signature(&signature, &ctx.accounts.signature_buffer)?,
+        )?;
+        {
+            let mut identity = ctx.accounts.identity.load_mut()?;
+            identity.mint_policy_count = identity.mint_policy_count.saturating_sub(1);
         }
 
-        // Accumulate the transfer amount
-        identity.current_window_amount = identity.current_window_amount.saturating_add(amount);
+        emit!(MintPolicyChanged {
+            authority: ctx.accounts.authority.key(),
+            mint: mint_policy.mint,
+            threshold_amount: 0,
+            velocity_limit: 0,
+            removed: true,
+        });
 
-        msg!(
-            "📊 Velocity Updated: window_total={}, window_start={}",
-            identity.current_window_amount,
-            identity.current_window_start
-        );
+        msg!("📊 Mint Policy Deleted: mint={}", mint_policy.mint);
 
         Ok(())
     }
-}
 
-// ============================================================================
-// ACCOUNT CONTEXTS
-// ============================================================================
+    /// Emergency freeze - locks the identity if key compromise is suspected.
+    /// The authority alone can freeze, since freezing only adds protection.
+    pub fn freeze(ctx: Context<ManageIdentity>) -> Result<()> {
+        let mut identity = ctx.accounts.identity.load_mut()?;
+        require!(identity.is_sealed != 0, QuresisError::IdentityNotSealed);
+        require!(identity.is_frozen == 0, QuresisError::IdentityFrozen);
 
-#[derive(Accounts)]
-#[instruction(pqc_public_key: Vec<u8>)]
-pub struct RegisterIdentity<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + QuantumIdentity::INIT_SPACE + pqc_public_key.len(),
-        seeds = [SEED_PREFIX, authority.key().as_ref()],
-        bump
-    )]
-    pub identity: Account<'info, QuantumIdentity>,
+        let slot = Clock::get()?.slot;
+        identity.is_frozen = 1;
+        identity.last_active_slot = slot;
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+        emit!(FreezeToggled {
+            authority: identity.authority,
+            is_frozen: true,
+            slot,
+        });
 
-    pub system_program: Program<'info, System>,
-}
+        msg!("🔒 Identity Freeze State: FROZEN");
 
-#[derive(Accounts)]
-#[instruction(new_pqc_public_key: Vec<u8>)]
-pub struct RotateKey<'info> {
-    #[account(
-        mut,
-        seeds = [SEED_PREFIX, authority.key().as_ref()],
-        bump = identity.bump,
-        has_one = authority,
-        // Realloc if new key is different size
-        realloc = 8 + QuantumIdentity::INIT_SPACE + new_pqc_public_key.len(),
-        realloc::payer = authority,
-        realloc::zero = false,
-    )]
-    pub identity: Account<'info, QuantumIdentity>,
+        Ok(())
+    }
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Emergency freeze authorized by the PQC key alone, for when the Ed25519
+    /// wallet itself is compromised. Takes the key's signature over
+    /// `FREEZE_DOMAIN || authority || sequence (u64 LE)` (the SDK's
+    /// `createFreezeMessage`), inline or in a finalized `signature_buffer`;
+    /// there is no wallet signer, so any relayer can submit it.
+    pub fn freeze_with_pqc(ctx: Context<FreezeWithPqc>, signature: Vec<u8>) -> Result<()> {
+        {
+            let identity = ctx.accounts.identity.load()?;
+            require!(identity.is_sealed != 0, QuresisError::IdentityNotSealed);
+            require!(identity.is_frozen == 0, QuresisError::IdentityFrozen);
+        }
 
-    pub system_program: Program<'info, System>,
-}
+        require_identity_action_signature(
+            &ctx.accounts.identity,
+            FREEZE_DOMAIN,
+            &[],
+            resolve_signature(&signature, &ctx.accounts.signature_buffer)?,
+        )?;
 
-#[derive(Accounts)]
-pub struct VerifySignature<'info> {
-    #[account(
-        seeds = [SEED_PREFIX, identity.authority.as_ref()],
-        bump = identity.bump,
-    )]
-    pub identity: Account<'info, QuantumIdentity>,
-    // Note: Signer not required - verification can be called by hooks/relayers
-}
+        let mut identity = ctx.accounts.identity.load_mut()?;
+        let slot = Clock::get()?.slot;
+        identity.sequence = identity.sequence.saturating_add(1);
+        identity.is_frozen = 1;
+        identity.last_active_slot = slot;
 
-#[derive(Accounts)]
-pub struct ManageIdentity<'info> {
-    #[account(
-        mut,
-        seeds = [SEED_PREFIX, authority.key().as_ref()],
-        bump = identity.bump,
-        has_one = authority
-    )]
-    pub identity: Account<'info, QuantumIdentity>,
+        emit!(FreezeToggled {
+            authority: identity.authority,
+            is_frozen: true,
+            slot,
+        });
 
-    pub authority: Signer<'info>,
-}
+        msg!("🔒 Identity Freeze State: FROZEN (quantum key)");
 
-#[derive(Accounts)]
-pub struct CloseIdentity<'info> {
-    #[account(
-        mut,
-        seeds = [SEED_PREFIX, authority.key().as_ref()],
-        bump = identity.bump,
-        has_one = authority,
-        close = authority
-    )]
-    pub identity: Account<'info, QuantumIdentity>,
+        Ok(())
+    }
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
-}
+    /// Lift a freeze. Needs the PQC key's signature over `UNFREEZE_DOMAIN ||
+    /// authority || sequence (u64 LE)` (the SDK's `createUnfreezeMessage`),
+    /// inline or in a finalized `signature_buffer`, so a stolen Ed25519 key
+    /// cannot undo an emergency freeze.
+    pub fn unfreeze(ctx: Context<AuthorizeIdentityUpdate>, signature: Vec<u8>) -> Result<()> {
+        {
+            let identity = ctx.accounts.identity.load()?;
+            require!(identity.is_sealed != 0, QuresisError::IdentityNotSealed);
+            require!(identity.is_frozen != 0, QuresisError::IdentityNotFrozen);
+        }
 
-#[derive(Accounts)]
-pub struct RecordTransfer<'info> {
-    #[account(
-        mut,
-        seeds = [SEED_PREFIX, identity.authority.as_ref()],
-        bump = identity.bump,
-    )]
-    pub identity: Account<'info, QuantumIdentity>,
-    // TODO: In production, enforce caller authorization via PDA registry or require identity authority as signer.
-    // MVP implementation allows any approved hook to CPI into this endpoint.
-}
+        require_identity_action_signature(
+            &ctx.accounts.identity,
+            UNFREEZE_DOMAIN,
+            &[],
+            resolve_signature(&signature, &ctx.accounts.signature_buffer)?,
+        )?;
 
-// ============================================================================
-// STATE
-// ============================================================================
+        let mut identity = ctx.accounts.identity.load_mut()?;
+        let slot = Clock::get()?.slot;
+        identity.sequence = identity.sequence.saturating_add(1);
+        identity.is_frozen = 0;
+        identity.last_active_slot = slot;
 
-#[account]
-#[derive(InitSpace)]
-pub struct QuantumIdentity {
-    /// The Solana wallet that owns this identity (Ed25519)
-    pub authority: Pubkey,                // 32 bytes
-    /// PDA bump seed
-    pub bump: u8,                         // 1 byte
-    /// Anti-replay nonce for PQC signatures
-    pub sequence: u64,                    // 8 bytes
-    /// Last activity slot
-    pub last_active_slot: u64,            // 8 bytes
-    /// Creation timestamp
-    pub created_at: i64,                  // 8 bytes
-    /// Emergency freeze flag
-    pub is_frozen: bool,                  // 1 byte
-    /// Transaction amount threshold requiring PQC signature
-    pub threshold_amount: u64,            // 8 bytes
-    /// Key version (incremented on rotation)
-    pub key_version: u16,                 // 2 bytes
-    /// Velocity tracking: start of current 24h window
-    pub current_window_start: i64,        // 8 bytes
-    /// Velocity tracking: total amount transferred in current window
-    pub current_window_amount: u64,       // 8 bytes
-    /// ML-DSA Public Key (variable size: 1312 or 1952 bytes)
-    #[max_len(2048)]
-    pub pqc_public_key: Vec<u8>,          // 4 + len bytes
-}
+        emit!(FreezeToggled {
+            authority: identity.authority,
+            is_frozen: false,
+            slot,
+        });
 
-impl QuantumIdentity {
-    /// Base space without the vector data
-    pub const INIT_SPACE: usize = 32 + 1 + 8 + 8 + 8 + 1 + 8 + 2 + 8 + 8 + 4;
-}
+        msg!("🔓 Identity Freeze State: ACTIVE");
 
-// ============================================================================
-// EVENTS
-// ============================================================================
+        Ok(())
+    }
 
-#[event]
-pub struct IdentityRegistered {
-    pub authority: Pubkey,
-    pub key_size: u16,
-    pub threshold: u64,
-    pub slot: u64,
-}
+    /// Revoke an identity. A sealed identity needs its PQC key's signature
+    /// over `CLOSE_IDENTITY_DOMAIN || authority || sequence (u64 LE)` (the
+    /// SDK's `createCloseMessage`), inline or in a finalized
+    /// `signature_buffer`, so the Ed25519 key alone cannot opt the wallet out.
+    ///
+    /// The account is not closed but shrunk to a header-only tombstone
+    /// (`is_revoked`) that the hook keeps blocking; the key's rent is
+    /// refunded. `reopen_identity` re-registers it after a cooldown.
+    ///
+    /// An unsealed staged registration is closed outright and its rent
+    /// refunded, unless it is a re-registration, which reverts to the tombstone.
+    pub fn close_identity(ctx: Context<CloseIdentity>, signature: Vec<u8>) -> Result<()> {
+        let (is_sealed, is_revoked, key_len) = {
+            let identity = ctx.accounts.identity.load()?;
+            (identity.is_sealed != 0, identity.is_revoked != 0, identity.key_len)
+        };
+
+        if !is_sealed && !is_revoked {
+            // Never opted in, so there is nothing for a tombstone to protect
+            ctx.accounts.identity.close(ctx.accounts.authority.to_account_info())?;
+            msg!("🗑️ Staged registration aborted, Identity Account Closed");
+            return Ok(());
+        }
+        require!(is_sealed || key_len != 0, QuresisError::IdentityRevoked);
+
+        if is_sealed {
+            require_identity_action_signature(
+                &ctx.accounts.identity,
+                CLOSE_IDENTITY_DOMAIN,
+                &[],
+                resolve_signature(&signature, &ctx.accounts.signature_buffer)?,
+            )?;
+        }
 
-#[event]
-pub struct KeyRotated {
-    pub authority: Pubkey,
-    pub old_version: u16,
-    pub new_version: u16,
-    pub new_key_size: u16,
-    pub slot: u64,
-}
+        resize_identity(
+            &ctx.accounts.identity,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            QuantumIdentity::HEADER_SPACE,
+        )?;
+
+        let mut identity = ctx.accounts.identity.load_mut()?;
+        let slot = Clock::get()?.slot;
+        if is_sealed {
+            identity.sequence = identity.sequence.saturating_add(1);
+        }
+        identity.key_len = 0;
+        identity.key_written_len = 0;
+        identity.is_sealed = 0;
+        identity.is_revoked = 1;
+        // The cooldown of `reopen_identity` counts from here
+        identity.last_active_slot = slot;
+
+        emit!(IdentityRevoked {
+            authority: identity.authority,
+            key_version: identity.key_version,
+            slot,
+        });
 
-#[event]
-pub struct SignatureVerified {
-    pub authority: Pubkey,
-    pub message_hash: [u8; 32],
-    pub slot: u64,
-}
+        msg!("🪦 Quantum Identity Revoked for: {}", identity.authority);
+        msg!(
+            "   Re-registration possible from slot {}",
+            slot.saturating_add(REREGISTRATION_COOLDOWN_SLOTS)
+        );
 
-#[event]
-pub struct ThresholdUpdated {
-    pub authority: Pubkey,
-    pub old_threshold: u64,
-    pub new_threshold: u64,
-}
+        Ok(())
+    }
 
-#[event]
-pub struct FreezeToggled {
-    pub authority: Pubkey,
-    pub is_frozen: bool,
-    pub slot: u64,
-}
+    /// Re-register a revoked identity (a `close_identity` tombstone) once
+    /// `REREGISTRATION_COOLDOWN_SLOTS` have passed since it was revoked.
+    /// Works like `begin_registration`: the new key is uploaded with
+    /// `write_key_chunk`, and the identity stays revoked until
+    /// `seal_identity` succeeds. The sequence and key version carry on, so
+    /// nothing signed before the revocation can be replayed.
+    pub fn reopen_identity(
+        ctx: Context<ReopenIdentity>,
+        key_len: u32,
+        threshold_amount: Option<u64>,
+    ) -> Result<()> {
+        require!(
+            is_supported_key_length(key_len as usize),
+            QuresisError::InvalidKeyLength
+        );
 
-// ============================================================================
-// ERRORS
-// ============================================================================
+        let threshold = threshold_amount.unwrap_or(DEFAULT_THRESHOLD);
+        require!(
+            (MIN_THRESHOLD..=MAX_THRESHOLD).contains(&threshold),
+            QuresisError::InvalidThreshold
+        );
 
-#[error_code]
-pub enum QuresisError {
-    #[msg("Invalid PQC public key length. Expected 1312 (ML-DSA-44) or 1952 (ML-DSA-65) bytes.")]
-    InvalidKeyLength,
+        let clock = Clock::get()?;
+        {
+            let identity = ctx.accounts.identity.load()?;
+            // The old wallet of a migrated identity is presumed compromised
+            require!(identity.is_forwarded == 0, QuresisError::IdentityForwarded);
+            require!(
+                identity.is_revoked != 0 && identity.key_len == 0,
+                QuresisError::IdentityNotRevoked
+            );
+            require!(
+                clock.slot >= identity.last_active_slot.saturating_add(REREGISTRATION_COOLDOWN_SLOTS),
+                QuresisError::ReregistrationCooldown
+            );
+        }
 
-    #[msg("Quantum signature verification failed.")]
-    InvalidQuantumSignature,
+        // The key region after the header is zero-extended by the resize
+        resize_identity(
+            &ctx.accounts.identity,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            QuantumIdentity::space(key_len as usize),
+        )?;
+
+        let mut identity = ctx.accounts.identity.load_mut()?;
+        identity.key_len = key_len as u16;
+        identity.key_written_len = 0;
+        identity.key_version = identity.key_version.saturating_add(1);
+        identity.threshold_amount = threshold;
+        identity.is_frozen = 0;
+        identity.last_active_slot = clock.slot;
+
+        msg!("📦 Re-registration started for: {}", identity.authority);
+        msg!("   PQC Key Size: {} bytes", key_len);
+
+        Ok(())
+    }
+
+    /// Move an identity to a new Ed25519 wallet, for when the old wallet's key
+    /// is compromised. Needs the PQC key's signature over
+    /// `MIGRATE_AUTHORITY_DOMAIN || authority || new_authority || sequence
+    /// (u64 LE)` (the SDK's `createMigrateAuthorityMessage`), inline or in a
+    /// finalized `signature_buffer`; the old wallet does not sign.
+    ///
+    /// `new_authority` signs and pays for a fresh identity PDA that carries
+    /// over the key, its version, the sequence, the threshold and the freeze
+    /// state. Per-mint `VelocityState` windows are keyed by the identity PDA,
+    /// so they stay with the tombstone and start fresh under the new
+    /// identity. The old identity becomes a permanent forwarding tombstone
+    /// (`is_forwarded`) holding `new_authority`, which the hook keeps
+    /// blocking and `reopen_identity` refuses.
+    ///
+    /// Every `MintPolicy` of the old identity moves too: the remaining
+    /// accounts are `mint_policy_count` pairs of an old policy and its PDA
+    /// under the new identity. Each is re-created there (`new_authority`
+    /// pays) and the old one is closed, its rent going to `new_authority`.
+    pub fn migrate_authority<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateAuthority<'info>>,
+        signature: Vec<u8>,
+    ) -> Result<()> {
+        let new_authority = ctx.accounts.new_authority.key();
+        let old = {
+            let identity = ctx.accounts.identity.load()?;
+            require!(identity.is_sealed != 0, QuresisError::IdentityNotSealed);
+            *identity
+        };
+
+        require_identity_action_signature(
+            &ctx.accounts.identity,
+            MIGRATE_AUTHORITY_DOMAIN,
+            new_authority.as_ref(),
+            resolve_signature(&signature, &ctx.accounts.signature_buffer)?,
+        )?;
+
+        identity_key_mut(&ctx.accounts.new_identity, old.key_len)?
+            .copy_from_slice(&identity_key(&ctx.accounts.identity, old.key_len)?);
+
+        let clock = Clock::get()?;
+        {
+            let mut identity = ctx.accounts.new_identity.load_init()?;
+            *identity = QuantumIdentity {
+                authority: new_authority,
+                // Continue the sequence so nothing signed for the old wallet replays
+                sequence: old.sequence.saturating_add(1),
+                last_active_slot: clock.slot,
+                bump: ctx.bumps.new_identity,
+                ..old
+            };
+        }
+
+        require!(
+            ctx.remaining_accounts.len() == 2 * old.mint_policy_count as usize,
+            QuresisError::MintPolicyCountMismatch
+        );
+        let old_identity = ctx.accounts.identity.key();
+        let new_identity = ctx.accounts.new_identity.key();
+        for pair in ctx.remaining_accounts.chunks(2) {
+            move_mint_policy(
+                &pair[0],
+                &pair[1],
+                &old_identity,
+                &new_identity,
+                &ctx.accounts.new_authority,
+                &ctx.accounts.system_program,
+            )?;
+        }
+
+        // Forwarding tombstone; the old key's rent goes to the new wallet
+        resize_identity(
+            &ctx.accounts.identity,
+            &ctx.accounts.new_authority,
+            &ctx.accounts.system_program,
+            QuantumIdentity::HEADER_SPACE + 32,
+        )?;
+        ctx.accounts.identity.as_ref().try_borrow_mut_data()?
+            [FORWARD_AUTHORITY_OFFSET..FORWARD_AUTHORITY_OFFSET + 32]
+            .copy_from_slice(new_authority.as_ref());
+
+        let mut identity = ctx.accounts.identity.load_mut()?;
+        identity.sequence = old.sequence.saturating_add(1);
+        identity.key_len = 0;
+        identity.key_written_len = 0;
+        identity.is_sealed = 0;
+        identity.is_revoked = 1;
+        identity.is_forwarded = 1;
+        identity.mint_policy_count = 0;
+        identity.last_active_slot = clock.slot;
+
+        emit!(AuthorityMigrated {
+            old_authority: old.authority,
+            new_authority,
+            key_version: old.key_version,
+            slot: clock.slot,
+        });
+
+        msg!("🚚 Quantum Identity moved: {} -> {}", old.authority, new_authority);
+        msg!("   Mint policies moved: {}", old.mint_policy_count);
+
+        Ok(())
+    }
+
+    /// Upgrade an identity stored in an older account layout to the current
+    /// one (`QUANTUM_IDENTITY_VERSION`), resizing it in place. Every field and
+    /// the key are carried over unchanged, so anyone may pay for it.
+    pub fn migrate_identity(ctx: Context<MigrateIdentity>) -> Result<()> {
+        let version = quantum_identity_version(&ctx.accounts.identity.as_ref().try_borrow_data()?)
+            .ok_or(QuresisError::UnsupportedIdentityVersion)?;
+        require!(version != QUANTUM_IDENTITY_VERSION, QuresisError::IdentityUpToDate);
+        require!(
+            version == QUANTUM_IDENTITY_VERSION_BORSH,
+            QuresisError::UnsupportedIdentityVersion
+        );
+
+        let legacy = QuantumIdentityBorsh::deserialize(
+            &mut &ctx.accounts.identity.as_ref().try_borrow_data()?[8..],
+        )?;
+        let key_len = legacy.pqc_public_key.len() as u16;
+
+        resize_identity(
+            &ctx.accounts.identity,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            QuantumIdentity::space(key_len as usize),
+        )?;
+        identity_key_mut(&ctx.accounts.identity, key_len)?
+            .copy_from_slice(&legacy.pqc_public_key);
+
+        let mut identity = ctx.accounts.identity.load_mut()?;
+        *identity = QuantumIdentity {
+            authority: legacy.authority,
+            sequence: legacy.sequence,
+            last_active_slot: legacy.last_active_slot,
+            created_at: legacy.created_at,
+            threshold_amount: legacy.threshold_amount,
+            current_window_start: legacy.current_window_start,
+            current_window_amount: legacy.current_window_amount,
+            key_version: legacy.key_version,
+            key_written_len: key_len,
+            key_len,
+            bump: legacy.bump,
+            is_frozen: legacy.is_frozen as u8,
+            is_sealed: 1,
+            version: QUANTUM_IDENTITY_VERSION,
+            is_revoked: 0,
+            is_forwarded: 0,
+            mint_policy_count: 0,
+            reserved: [0; 2],
+        };
+
+        emit!(IdentityMigrated {
+            authority: identity.authority,
+            from_version: version,
+            to_version: QUANTUM_IDENTITY_VERSION,
+        });
+
+        msg!(
+            "🧬 Quantum Identity Migrated: layout v{} -> v{}",
+            version,
+            QUANTUM_IDENTITY_VERSION
+        );
+
+        Ok(())
+    }
+
+    /// Allocate a SignatureBuffer for staging an ML-DSA signature (or a new
+    /// public key for `rotate_key`) that is too large for a single
+    /// transaction (1232-byte MTU).
+    /// `buffer_id` lets one wallet stage several uploads concurrently.
+    pub fn init_buffer(
+        ctx: Context<InitBuffer>,
+        buffer_id: u64,
+        data_len: u32,
+    ) -> Result<()> {
+        let len = data_len as usize;
+        require!(
+            len == ML_DSA_44_SIG_SIZE
+                || len == ML_DSA_65_SIG_SIZE
+                || len == ML_DSA_44_PUBKEY_SIZE
+                || len == ML_DSA_65_PUBKEY_SIZE,
+            QuresisError::InvalidSignatureSize
+        );
+
+        let buffer = &mut ctx.accounts.signature_buffer;
+        buffer.authority = ctx.accounts.authority.key();
+        buffer.buffer_id = buffer_id;
+        buffer.bump = ctx.bumps.signature_buffer;
+        buffer.expected_len = data_len;
+        buffer.written_len = 0;
+        buffer.is_finalized = false;
+        buffer.data = vec![0u8; len];
+
+        msg!("📦 Signature Buffer #{} allocated: {} bytes", buffer_id, data_len);
+
+        Ok(())
+    }
+
+    /// Write a chunk of buffer bytes at `offset`.
+    /// Chunks may be rewritten but must be contiguous: `offset` can never
+    /// skip past the bytes already written.
+    pub fn write_chunk(ctx: Context<ManageBuffer>, offset: u32, data: Vec<u8>) -> Result<()> {
+        let buffer = &mut ctx.accounts.signature_buffer;
+        require!(!buffer.is_finalized, QuresisError::BufferAlreadyFinalized);
+
+        let start = offset as usize;
+        let end = start
+            .checked_add(data.len())
+            .ok_or(QuresisError::ChunkOutOfBounds)?;
+        require!(
+            start <= buffer.written_len as usize && end <= buffer.expected_len as usize,
+            QuresisError::ChunkOutOfBounds
+        );
+
+        buffer.data[start..end].copy_from_slice(&data);
+        buffer.written_len = buffer.written_len.max(end as u32);
+
+        msg!(
+            "📦 Signature Buffer #{}: {}/{} bytes",
+            buffer.buffer_id,
+            buffer.written_len,
+            buffer.expected_len
+        );
+
+        Ok(())
+    }
+
+    /// Seal a fully written buffer. A finalized buffer is immutable and can
+    /// be passed to `verify_signature` / `rotate_key` in place of inline bytes.
+    pub fn finalize(ctx: Context<ManageBuffer>) -> Result<()> {
+        let buffer = &mut ctx.accounts.signature_buffer;
+        require!(!buffer.is_finalized, QuresisError::BufferAlreadyFinalized);
+        require!(
+            buffer.written_len == buffer.expected_len,
+            QuresisError::BufferIncomplete
+        );
+
+        buffer.is_finalized = true;
+
+        msg!("🔏 Signature Buffer #{} finalized", buffer.buffer_id);
+
+        Ok(())
+    }
+
+    /// Close a SignatureBuffer (finalized or not) and refund its rent.
+    pub fn close_buffer(_ctx: Context<CloseBuffer>) -> Result<()> {
+        msg!("🗑️ Signature Buffer Closed");
+        Ok(())
+    }
+
+    /// Open a VerificationSession: an ML-DSA verification that runs across
+    /// several transactions when it does not fit one compute budget.
+    ///
+    /// This step validates the signature encoding, decodes `z` and absorbs
+    /// `μ`. Call `advance_verification` until the session is `Verified` or
+    /// `Rejected`, then hand it to `verify_signature`. The signature comes
+    /// inline or from a finalized `SignatureBuffer`, as for `verify_signature`.
+    pub fn begin_verification(
+        ctx: Context<BeginVerification>,
+        session_id: u64,
+        message: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<()> {
+        let identity = ctx.accounts.identity.load()?;
+        let public_key = identity_key(&ctx.accounts.identity, identity.key_len)?;
+        let signature = resolve_signature(&signature, &ctx.accounts.signature_buffer)?;
+
+        require!(identity.is_sealed != 0, QuresisError::IdentityNotSealed);
+        require!(identity.is_frozen == 0, QuresisError::IdentityFrozen);
+
+        let params = ml_dsa::ParameterSet::for_public_key(&public_key)
+            .ok_or(QuresisError::InvalidKeyLength)?;
+        require!(
+            signature.len() == params.signature_len(),
+            QuresisError::InvalidSignatureSize
+        );
+
+        let clock = Clock::get()?;
+        let session = &mut ctx.accounts.verification_session;
+        session.authority = ctx.accounts.authority.key();
+        session.identity = ctx.accounts.identity.key();
+        session.key_version = identity.key_version;
+        session.session_id = session_id;
+        session.bump = ctx.bumps.verification_session;
+        session.next_step = 0;
+        session.expires_at_slot = clock.slot.saturating_add(VERIFY_SESSION_TTL_SLOTS);
+        session.message_hash = hash_message(&message);
+
+        match ml_dsa::resumable::start(params, &public_key, &message, signature) {
+            Some(started) => {
+                session.status = VerificationStatus::InProgress;
+                session.c_tilde = started.c_tilde;
+                session.hint = started.hint;
+                session.z_hat = started.z;
+                session.hasher_state = started.hasher_state;
+                msg!("🧮 Verification Session #{} started ({})", session_id, params.name);
+            }
+            None => {
+                // Malformed encodings are a verdict, not an error
+                complete_verification_session(session, false, clock.slot);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run the next step of a VerificationSession. Permissionless: the
+    /// outcome depends only on the data fixed in `begin_verification`.
+    ///
+    /// Step 0 moves `z` to the NTT domain and samples `ĉ`; step i (1..=k)
+    /// folds row i-1 of `Â` into the challenge hash, and the last row also
+    /// compares it with `c̃`.
+    pub fn advance_verification(ctx: Context<AdvanceVerification>) -> Result<()> {
+        let identity = ctx.accounts.identity.load()?;
+        let public_key = identity_key(&ctx.accounts.identity, identity.key_len)?;
+        let session = &mut ctx.accounts.verification_session;
+        let slot = Clock::get()?.slot;
+
+        require!(
+            session.status == VerificationStatus::InProgress,
+            QuresisError::SessionNotInProgress
+        );
+        require!(slot <= session.expires_at_slot, QuresisError::SessionExpired);
+        require!(
+            session.key_version == identity.key_version,
+            QuresisError::SessionIdentityMismatch
+        );
+
+        let params = ml_dsa::ParameterSet::for_public_key(&public_key)
+            .ok_or(QuresisError::InvalidKeyLength)?;
+
+        if session.next_step == 0 {
+            let session = &mut **session;
+            session.c_hat = ml_dsa::resumable::transform(params, &session.c_tilde, &mut session.z_hat);
+            session.next_step = 1;
+            msg!("🧮 Verification Session #{}: NTT(z) done", session.session_id);
+            return Ok(());
+        }
+
+        let row = (session.next_step - 1) as usize;
+        let session = &mut **session;
+        let ok = ml_dsa::resumable::absorb_row(
+            params,
+            &public_key,
+            &session.hint,
+            row,
+            &session.z_hat,
+            &session.c_hat,
+            &mut session.hasher_state,
+        );
+        if !ok {
+            complete_verification_session(session, false, slot);
+            return Ok(());
+        }
+
+        session.next_step += 1;
+        msg!(
+            "🧮 Verification Session #{}: row {}/{} done",
+            session.session_id,
+            row + 1,
+            params.k
+        );
+
+        if row + 1 == params.k {
+            let valid =
+                ml_dsa::resumable::finish(params, &session.c_tilde, &session.hasher_state);
+            complete_verification_session(session, valid, slot);
+        }
+
+        Ok(())
+    }
+
+    /// Close a VerificationSession in any state and refund its rent.
+    pub fn close_verification(_ctx: Context<CloseVerification>) -> Result<()> {
+        msg!("🗑️ Verification Session Closed");
+        Ok(())
+    }
+
+    /// Spend a TransferAuthorization on a transfer.
+    /// Called via CPI by the transfer hook when a HardEnforce transfer is
+    /// above the threshold. The receipt is marked consumed rather than
+    /// closed, because the hook cannot pass its payer; anyone can then
+    /// return its rent to the payer with `close_transfer_authorization`.
+    pub fn consume_transfer_authorization(
+        ctx: Context<ConsumeTransferAuthorization>,
+        amount: u64,
+    ) -> Result<()> {
+        let identity = ctx.accounts.identity.load()?;
+        let receipt = &mut ctx.accounts.transfer_authorization;
+        let slot = Clock::get()?.slot;
+
+        require!(identity.is_sealed != 0, QuresisError::IdentityNotSealed);
+        require!(identity.is_frozen == 0, QuresisError::IdentityFrozen);
+        require!(!receipt.consumed, QuresisError::AuthorizationConsumed);
+        require!(
+            slot <= receipt.expires_at_slot,
+            QuresisError::AuthorizationExpired
+        );
+        require_keys_eq!(
+            receipt.destination,
+            ctx.accounts.destination.key(),
+            QuresisError::AuthorizationDestinationMismatch
+        );
+        require!(
+            amount <= receipt.amount_cap,
+            QuresisError::AuthorizationAmountExceeded
+        );
+        receipt.consumed = true;
+
+        emit!(TransferAuthorizationConsumed {
+            authority: identity.authority,
+            mint: receipt.mint,
+            destination: receipt.destination,
+            amount,
+            sequence: receipt.sequence,
+            slot,
+        });
+
+        msg!("🎫 Transfer Authorization consumed: {} of {}", amount, receipt.amount_cap);
+
+        Ok(())
+    }
+
+    /// Close an unused TransferAuthorization (authority-only) and refund its
+    /// rent to whoever paid for it.
+    pub fn revoke_transfer_authorization(
+        _ctx: Context<RevokeTransferAuthorization>,
+    ) -> Result<()> {
+        msg!("🗑️ Transfer Authorization Revoked");
+        Ok(())
+    }
+
+    /// Close a consumed or expired TransferAuthorization and refund its rent
+    /// to whoever paid for it. Permissionless: the receipt can no longer
+    /// authorize anything, and the rent only goes back to its payer.
+    ///
+    /// Receipts minted before `payer` was recorded cannot be consumed any
+    /// more; once expired, their rent goes to the identity, where consuming
+    /// them used to put it.
+    pub fn close_transfer_authorization(
+        ctx: Context<CloseTransferAuthorization>,
+    ) -> Result<()> {
+        let receipt = ctx.accounts.transfer_authorization.to_account_info();
+        let slot = Clock::get()?.slot;
+        let (refund_to, closable) = {
+            let data = receipt.try_borrow_data()?;
+            if data.len() == TransferAuthorization::LEGACY_LEN {
+                require!(
+                    data.starts_with(TransferAuthorization::DISCRIMINATOR),
+                    ErrorCode::AccountDiscriminatorMismatch
+                );
+                // identity (8..40), expires_at_slot (112..120)
+                let identity = Pubkey::try_from(&data[8..40]).unwrap();
+                let expires_at_slot = u64::from_le_bytes(data[112..120].try_into().unwrap());
+                (identity, slot > expires_at_slot)
+            } else {
+                let current = TransferAuthorization::try_deserialize(&mut &data[..])?;
+                (current.payer, current.consumed || slot > current.expires_at_slot)
+            }
+        };
+        require!(closable, QuresisError::AuthorizationActive);
+
+        let rent_receiver = ctx.accounts.rent_receiver.to_account_info();
+        require_keys_eq!(rent_receiver.key(), refund_to, QuresisError::RentReceiverMismatch);
+        rent_receiver.add_lamports(receipt.lamports())?;
+        receipt.sub_lamports(receipt.lamports())?;
+        receipt.assign(&System::id());
+        receipt.resize(0)?;
+
+        msg!("🗑️ Transfer Authorization Closed");
+        Ok(())
+    }
+
+    /// Add a hook program to the registry of callers allowed to CPI into
+    /// `record_transfer` and `consume_transfer_authorization`.
+    /// Only the upgrade authority of this program can approve hooks.
+    pub fn approve_hook(ctx: Context<ApproveHook>, hook_program: Pubkey) -> Result<()> {
+        let (caller, _) = Pubkey::find_program_address(&[HOOK_CALLER_SEED], &hook_program);
+
+        let approved_hook = &mut ctx.accounts.approved_hook;
+        approved_hook.hook_program = hook_program;
+        approved_hook.caller = caller;
+        approved_hook.approved_by = ctx.accounts.admin.key();
+        approved_hook.bump = ctx.bumps.approved_hook;
+
+        emit!(HookApprovalChanged {
+            hook_program,
+            approved: true,
+            admin: ctx.accounts.admin.key(),
+        });
+
+        msg!("🔌 Hook Approved: {} (signs as {})", hook_program, caller);
+
+        Ok(())
+    }
+
+    /// Remove a hook program from the registry (upgrade authority only).
+    pub fn revoke_hook(ctx: Context<RevokeHook>) -> Result<()> {
+        emit!(HookApprovalChanged {
+            hook_program: ctx.accounts.approved_hook.hook_program,
+            approved: false,
+            admin: ctx.accounts.admin.key(),
+        });
+
+        msg!("🔌 Hook Revoked: {}", ctx.accounts.approved_hook.hook_program);
+
+        Ok(())
+    }
+
+    /// Create the `VelocityState` of `identity` on `mint` ahead of its first
+    /// transfer, paid by `payer` (normally the identity's wallet) instead of
+    /// the mint's HookConfig. Does nothing if the window already exists, so
+    /// it can be prepended to any transfer.
+    pub fn init_velocity_state(ctx: Context<InitVelocityState>) -> Result<()> {
+        let velocity_info = ctx.accounts.velocity_state.to_account_info();
+        if !velocity_info.data_is_empty() {
+            msg!("📊 Velocity State already exists for mint {}", ctx.accounts.mint.key());
+            return Ok(());
+        }
+
+        let identity_key = ctx.accounts.identity.key();
+        let mint_key = ctx.accounts.mint.key();
+        let bump = ctx.bumps.velocity_state;
+        create_pda(
+            &velocity_info,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            &[VELOCITY_SEED, identity_key.as_ref(), mint_key.as_ref(), &[bump]],
+            quresis_common::VELOCITY_STATE_LEN,
+        )?;
+
+        // An empty window; `record_transfer` starts it on the first transfer
+        let velocity = VelocityState {
+            identity: identity_key,
+            mint: mint_key,
+            window_start: 0,
+            window_amount: 0,
+            bump,
+        };
+        velocity.try_serialize(&mut &mut velocity_info.try_borrow_mut_data()?[..])?;
+
+        msg!("📊 Velocity State Created: mint={}, payer={}", mint_key, ctx.accounts.payer.key());
+
+        Ok(())
+    }
+
+    /// Record a transfer in the sender's velocity window for `mint`.
+    /// Only callable via CPI by an approved hook (see `approve_hook`).
+    /// `amount` is in threshold units (`THRESHOLD_DECIMALS`), already
+    /// normalized from the mint's decimals. Each (identity, mint) pair has its
+    /// own `VelocityState`. Unless `init_velocity_state` created it already,
+    /// it is created here on the pair's first transfer with rent from the
+    /// hook's signer PDA.
+    pub fn record_transfer(ctx: Context<RecordTransfer>, amount: u64) -> Result<()> {
+        {
+            let identity = ctx.accounts.identity.load()?;
+            require!(identity.is_sealed != 0, QuresisError::IdentityNotSealed);
+            require!(identity.is_frozen == 0, QuresisError::IdentityFrozen);
+        }
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let window_size: i64 = 24 * 60 * 60; // 24 hours 
+
+        let velocity_info = ctx.accounts.velocity_state.to_account_info();
+        let mut velocity = if velocity_info.data_is_empty() {
+            let identity_key = ctx.accounts.identity.key();
+            let mint_key = ctx.accounts.mint.key();
+            let bump = ctx.bumps.velocity_state;
+            create_pda(
+                &velocity_info,
+                &ctx.accounts.hook_signer,
+                &ctx.accounts.system_program,
+                &[VELOCITY_SEED, identity_key.as_ref(), mint_key.as_ref(), &[bump]],
+                quresis_common::VELOCITY_STATE_LEN,
+            )?;
+            VelocityState {
+                identity: identity_key,
+                mint: mint_key,
+                window_start: current_time,
+                window_amount: 0,
+                bump,
+            }
+        } else {
+            require_keys_eq!(
+                *velocity_info.owner,
+                crate::ID,
+                anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram
+            );
+            VelocityState::try_deserialize(&mut &velocity_info.try_borrow_data()?[..])?
+        };
+
+        // Check if we need to reset the sliding window
+        if current_time >= velocity.window_start.saturating_add(window_size) {
+            velocity.window_start = current_time;
+            velocity.window_amount = 0;
+        }
+
+        // Accumulate the transfer amount
+        velocity.window_amount = velocity.window_amount.saturating_add(amount);
+        velocity.try_serialize(&mut &mut velocity_info.try_borrow_mut_data()?[..])?;
+
+        msg!(
+            "📊 Velocity Updated: mint={}, window_total={}, window_start={}",
+            velocity.mint,
+            velocity.window_amount,
+            velocity.window_start
+        );
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// ACCOUNT CONTEXTS
+// ============================================================================
+
+#[derive(Accounts)]
+#[instruction(pqc_public_key: Vec<u8>)]
+pub struct RegisterIdentity<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = QuantumIdentity::space(pqc_public_key.len()),
+        seeds = [SEED_PREFIX, authority.key().as_ref()],
+        bump
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Finalized buffer holding `possession_signature` (pass an empty Vec inline)
+    pub signature_buffer: Option<Account<'info, SignatureBuffer>>,
+}
+
+#[derive(Accounts)]
+#[instruction(key_len: u32)]
+pub struct BeginRegistration<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = QuantumIdentity::space(key_len as usize),
+        seeds = [SEED_PREFIX, authority.key().as_ref()],
+        bump
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RotateKey<'info> {
+    /// Resized in the handler once the old key has signed (see `resize_identity`)
+    #[account(
+        mut,
+        seeds = [SEED_PREFIX, authority.key().as_ref()],
+        bump = load_identity(&identity)?.bump,
+        has_one = authority,
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Finalized buffer holding `old_key_signature` (pass an empty Vec inline)
+    pub signature_buffer: Option<Account<'info, SignatureBuffer>>,
+
+    /// Finalized buffer holding `new_pqc_public_key` (pass an empty Vec inline)
+    pub new_key_buffer: Option<Account<'info, SignatureBuffer>>,
+
+    /// Finalized buffer holding `new_key_signature` (pass an empty Vec inline)
+    pub new_key_signature_buffer: Option<Account<'info, SignatureBuffer>>,
+}
+
+#[derive(Accounts)]
+#[instruction(message: Vec<u8>, signature: Vec<u8>, authorization: Option<TransferAuthorizationParams>)]
+pub struct VerifySignature<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_PREFIX, identity.load()?.authority.as_ref()],
+        bump = load_identity(&identity)?.bump,
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+    // Note: Signer not required - verification can be called by hooks/relayers
+
+    /// Finalized buffer holding the signature (pass an empty Vec inline)
+    pub signature_buffer: Option<Account<'info, SignatureBuffer>>,
+
+    /// Completed session standing in for the signature (pass an empty Vec inline)
+    #[account(mut)]
+    pub verification_session: Option<Account<'info, VerificationSession>>,
+
+    /// Receipt minted when `authorization` is set
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + TransferAuthorization::INIT_SPACE,
+        seeds = [
+            TRANSFER_AUTH_SEED,
+            identity.key().as_ref(),
+            authorization.as_ref().map(|a| a.mint).unwrap_or_default().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_authorization: Option<Account<'info, TransferAuthorization>>,
+
+    /// Pays the receipt's rent (only needed with `transfer_authorization`)
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
+
+    pub system_program: Option<Program<'info, System>>,
+
+    /// Identity owner; must sign for a transfer message without a receipt
+    pub authority: Option<Signer<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct ManageIdentity<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_PREFIX, authority.key().as_ref()],
+        bump = load_identity(&identity)?.bump,
+        has_one = authority
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AuthorizeIdentityUpdate<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_PREFIX, authority.key().as_ref()],
+        bump = load_identity(&identity)?.bump,
+        has_one = authority
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    pub authority: Signer<'info>,
+
+    /// Finalized buffer holding the PQC signature (pass an empty Vec inline)
+    pub signature_buffer: Option<Account<'info, SignatureBuffer>>,
+}
+
+#[derive(Accounts)]
+pub struct CreateMintPolicy<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_PREFIX, authority.key().as_ref()],
+        bump = load_identity(&identity)?.bump,
+        has_one = authority
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MintPolicy::INIT_SPACE,
+        seeds = [MINT_POLICY_SEED, identity.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_policy: Account<'info, MintPolicy>,
+
+    /// CHECK: Only a seed; the Token-2022 mint the policy applies to
+    pub mint: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Finalized buffer holding the PQC signature (pass an empty Vec inline)
+    pub signature_buffer: Option<Account<'info, SignatureBuffer>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMintPolicy<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_PREFIX, authority.key().as_ref()],
+        bump = load_identity(&identity)?.bump,
+        has_one = authority
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    #[account(
+        mut,
+        seeds = [MINT_POLICY_SEED, identity.key().as_ref(), mint_policy.mint.as_ref()],
+        bump = mint_policy.bump,
+        has_one = identity
+    )]
+    pub mint_policy: Account<'info, MintPolicy>,
+
+    pub authority: Signer<'info>,
+
+    /// Finalized buffer holding the PQC signature (pass an empty Vec inline)
+    pub signature_buffer: Option<Account<'info, SignatureBuffer>>,
+}
+
+#[derive(Accounts)]
+pub struct DeleteMintPolicy<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_PREFIX, authority.key().as_ref()],
+        bump = load_identity(&identity)?.bump,
+        has_one = authority
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    #[account(
+        mut,
+        seeds = [MINT_POLICY_SEED, identity.key().as_ref(), mint_policy.mint.as_ref()],
+        bump = mint_policy.bump,
+        has_one = identity,
+        close = authority
+    )]
+    pub mint_policy: Account<'info, MintPolicy>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Finalized buffer holding the PQC signature (pass an empty Vec inline)
+    pub signature_buffer: Option<Account<'info, SignatureBuffer>>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeWithPqc<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_PREFIX, identity.load()?.authority.as_ref()],
+        bump = load_identity(&identity)?.bump,
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+    // Note: no Signer - the PQC signature is the authorization, so a relayer
+    // can submit it when the wallet is compromised
+
+    /// Finalized buffer holding the PQC signature (pass an empty Vec inline)
+    pub signature_buffer: Option<Account<'info, SignatureBuffer>>,
+}
+
+#[derive(Accounts)]
+pub struct SealIdentity<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_PREFIX, authority.key().as_ref()],
+        bump = load_identity(&identity)?.bump,
+        has_one = authority
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    pub authority: Signer<'info>,
+
+    /// Finalized buffer holding `possession_signature` (pass an empty Vec inline)
+    pub signature_buffer: Option<Account<'info, SignatureBuffer>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseIdentity<'info> {
+    /// Shrunk to a tombstone (or closed, if never sealed) by the handler
+    #[account(
+        mut,
+        seeds = [SEED_PREFIX, authority.key().as_ref()],
+        bump = load_identity(&identity)?.bump,
+        has_one = authority,
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    /// Receives the refunded rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Finalized buffer holding `signature` (pass an empty Vec inline)
+    pub signature_buffer: Option<Account<'info, SignatureBuffer>>,
+}
+
+#[derive(Accounts)]
+pub struct ReopenIdentity<'info> {
+    /// Grown back to fit the new key by the handler
+    #[account(
+        mut,
+        seeds = [SEED_PREFIX, authority.key().as_ref()],
+        bump = load_identity(&identity)?.bump,
+        has_one = authority,
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAuthority<'info> {
+    /// Left behind as a forwarding tombstone by the handler
+    #[account(
+        mut,
+        seeds = [SEED_PREFIX, identity.load()?.authority.as_ref()],
+        bump = load_identity(&identity)?.bump,
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+    // Note: the old authority does not sign - its key is presumed compromised
+
+    #[account(
+        init,
+        payer = new_authority,
+        space = QuantumIdentity::space(identity.load()?.key_len as usize),
+        seeds = [SEED_PREFIX, new_authority.key().as_ref()],
+        bump
+    )]
+    pub new_identity: AccountLoader<'info, QuantumIdentity>,
+
+    #[account(mut)]
+    pub new_authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Finalized buffer holding the PQC signature (pass an empty Vec inline)
+    pub signature_buffer: Option<Account<'info, SignatureBuffer>>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateIdentity<'info> {
+    /// Any layout version; checked and converted by the handler
+    #[account(
+        mut,
+        seeds = [SEED_PREFIX, authority.key().as_ref()],
+        bump
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    /// CHECK: Owner of the identity, only used for the PDA seeds (migration
+    /// changes no field, so it need not sign)
+    pub authority: UncheckedAccount<'info>,
+
+    /// Pays the rent for the larger layout
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(buffer_id: u64, data_len: u32)]
+pub struct InitBuffer<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SignatureBuffer::INIT_SPACE + data_len as usize,
+        seeds = [SIG_BUFFER_SEED, authority.key().as_ref(), &buffer_id.to_le_bytes()],
+        bump
+    )]
+    pub signature_buffer: Account<'info, SignatureBuffer>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageBuffer<'info> {
+    #[account(
+        mut,
+        seeds = [
+            SIG_BUFFER_SEED,
+            authority.key().as_ref(),
+            &signature_buffer.buffer_id.to_le_bytes()
+        ],
+        bump = signature_buffer.bump,
+        has_one = authority
+    )]
+    pub signature_buffer: Account<'info, SignatureBuffer>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBuffer<'info> {
+    #[account(
+        mut,
+        seeds = [
+            SIG_BUFFER_SEED,
+            authority.key().as_ref(),
+            &signature_buffer.buffer_id.to_le_bytes()
+        ],
+        bump = signature_buffer.bump,
+        has_one = authority,
+        close = authority
+    )]
+    pub signature_buffer: Account<'info, SignatureBuffer>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: u64)]
+pub struct BeginVerification<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VerificationSession::INIT_SPACE,
+        seeds = [VERIFY_SESSION_SEED, authority.key().as_ref(), &session_id.to_le_bytes()],
+        bump
+    )]
+    pub verification_session: Account<'info, VerificationSession>,
+
+    #[account(
+        seeds = [SEED_PREFIX, identity.load()?.authority.as_ref()],
+        bump = load_identity(&identity)?.bump,
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    /// Finalized buffer holding the signature (pass an empty Vec inline)
+    pub signature_buffer: Option<Account<'info, SignatureBuffer>>,
+
+    /// Opens the session and pays its rent (need not be the identity owner)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdvanceVerification<'info> {
+    #[account(
+        mut,
+        seeds = [
+            VERIFY_SESSION_SEED,
+            verification_session.authority.as_ref(),
+            &verification_session.session_id.to_le_bytes()
+        ],
+        bump = verification_session.bump,
+        has_one = identity,
+    )]
+    pub verification_session: Account<'info, VerificationSession>,
+
+    #[account(
+        seeds = [SEED_PREFIX, identity.load()?.authority.as_ref()],
+        bump = load_identity(&identity)?.bump,
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVerification<'info> {
+    #[account(
+        mut,
+        seeds = [
+            VERIFY_SESSION_SEED,
+            authority.key().as_ref(),
+            &verification_session.session_id.to_le_bytes()
+        ],
+        bump = verification_session.bump,
+        has_one = authority,
+        close = authority
+    )]
+    pub verification_session: Account<'info, VerificationSession>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConsumeTransferAuthorization<'info> {
+    #[account(
+        seeds = [SEED_PREFIX, identity.load()?.authority.as_ref()],
+        bump = load_identity(&identity)?.bump,
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    #[account(
+        mut,
+        seeds = [TRANSFER_AUTH_SEED, identity.key().as_ref(), mint.key().as_ref()],
+        bump = transfer_authorization.bump,
+        has_one = identity,
+        has_one = mint,
+    )]
+    pub transfer_authorization: Account<'info, TransferAuthorization>,
+
+    /// CHECK: Only compared against the authorized mint
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: Only compared against the authorized destination
+    pub destination: UncheckedAccount<'info>,
+
+    /// Registry entry of the calling hook program
+    #[account(
+        seeds = [APPROVED_HOOK_SEED, approved_hook.hook_program.as_ref()],
+        bump = approved_hook.bump,
+        constraint = approved_hook.caller == hook_signer.key() @ QuresisError::UnauthorizedCaller,
+    )]
+    pub approved_hook: Account<'info, ApprovedHook>,
+
+    /// `[HOOK_CALLER_SEED]` PDA of the approved hook; only that program can sign for it
+    pub hook_signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeTransferAuthorization<'info> {
+    #[account(
+        seeds = [SEED_PREFIX, authority.key().as_ref()],
+        bump = load_identity(&identity)?.bump,
+        has_one = authority
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    #[account(
+        mut,
+        seeds = [TRANSFER_AUTH_SEED, identity.key().as_ref(), transfer_authorization.mint.as_ref()],
+        bump = transfer_authorization.bump,
+        has_one = identity,
+        close = rent_receiver
+    )]
+    pub transfer_authorization: Account<'info, TransferAuthorization>,
+
+    pub authority: Signer<'info>,
+
+    /// CHECK: Must be the account that paid for the receipt
+    #[account(
+        mut,
+        address = transfer_authorization.payer @ QuresisError::RentReceiverMismatch
+    )]
+    pub rent_receiver: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseTransferAuthorization<'info> {
+    /// CHECK: Current or pre-`payer` receipt layout; parsed by the handler
+    #[account(mut, owner = crate::ID)]
+    pub transfer_authorization: UncheckedAccount<'info>,
+
+    /// CHECK: Checked against the receipt's payer by the handler
+    #[account(mut)]
+    pub rent_receiver: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RecordTransfer<'info> {
+    #[account(
+        seeds = [SEED_PREFIX, identity.load()?.authority.as_ref()],
+        bump = load_identity(&identity)?.bump,
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    /// Velocity window of this identity on `mint`; empty until its first transfer
+    /// CHECK: Seeds verified; created and (de)serialized in `record_transfer`
+    #[account(
+        mut,
+        seeds = [VELOCITY_SEED, identity.key().as_ref(), mint.key().as_ref()],
+        bump,
+    )]
+    pub velocity_state: UncheckedAccount<'info>,
+
+    /// CHECK: Only a seed; the approved hook passes the mint being transferred
+    pub mint: UncheckedAccount<'info>,
+
+    /// Registry entry of the calling hook program
+    #[account(
+        seeds = [APPROVED_HOOK_SEED, approved_hook.hook_program.as_ref()],
+        bump = approved_hook.bump,
+        constraint = approved_hook.caller == hook_signer.key() @ QuresisError::UnauthorizedCaller,
+    )]
+    pub approved_hook: Account<'info, ApprovedHook>,
+
+    /// `[HOOK_CALLER_SEED]` PDA of the approved hook; only that program can sign for it.
+    /// Pays the rent of a new `velocity_state` (the hook funds it first).
+    #[account(mut)]
+    pub hook_signer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitVelocityState<'info> {
+    #[account(
+        seeds = [SEED_PREFIX, identity.load()?.authority.as_ref()],
+        bump = load_identity(&identity)?.bump,
+    )]
+    pub identity: AccountLoader<'info, QuantumIdentity>,
+
+    /// Velocity window of this identity on `mint`
+    /// CHECK: Seeds verified; created in `init_velocity_state` unless it exists
+    #[account(
+        mut,
+        seeds = [VELOCITY_SEED, identity.key().as_ref(), mint.key().as_ref()],
+        bump,
+    )]
+    pub velocity_state: UncheckedAccount<'info>,
+
+    /// CHECK: Only a seed; the mint whose transfers the window counts
+    pub mint: UncheckedAccount<'info>,
+
+    /// Pays the rent of the new `velocity_state`
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(hook_program: Pubkey)]
+pub struct ApproveHook<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ApprovedHook::INIT_SPACE,
+        seeds = [APPROVED_HOOK_SEED, hook_program.as_ref()],
+        bump
+    )]
+    pub approved_hook: Account<'info, ApprovedHook>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+    )]
+    pub program: Program<'info, crate::program::Quresis>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ QuresisError::UnauthorizedAdmin
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeHook<'info> {
+    #[account(
+        mut,
+        seeds = [APPROVED_HOOK_SEED, approved_hook.hook_program.as_ref()],
+        bump = approved_hook.bump,
+        close = admin
+    )]
+    pub approved_hook: Account<'info, ApprovedHook>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+    )]
+    pub program: Program<'info, crate::program::Quresis>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ QuresisError::UnauthorizedAdmin
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+// ============================================================================
+// STATE
+// ============================================================================
+
+/// Fixed zero-copy header; the ML-DSA public key (`key_len` bytes) follows
+/// it directly in the account data (see `identity_key`). Fields are ordered
+/// so that `repr(C)` inserts no padding.
+#[account(zero_copy)]
+pub struct QuantumIdentity {
+    /// The Solana wallet that owns this identity (Ed25519)
+    pub authority: Pubkey,                // 32 bytes
+    /// Anti-replay nonce for PQC signatures
+    pub sequence: u64,                    // 8 bytes
+    /// Last activity slot
+    pub last_active_slot: u64,            // 8 bytes
+    /// Creation timestamp
+    pub created_at: i64,                  // 8 bytes
+    /// Transaction amount threshold requiring PQC signature
+    pub threshold_amount: u64,            // 8 bytes
+    /// Unused since velocity moved to the per-mint `VelocityState`; kept so
+    /// the layout does not change
+    pub current_window_start: i64,        // 8 bytes
+    /// Unused, see `current_window_start`
+    pub current_window_amount: u64,       // 8 bytes
+    /// Key version (incremented on rotation)
+    pub key_version: u16,                 // 2 bytes
+    /// Staged registration: contiguous key bytes written so far
+    pub key_written_len: u16,             // 2 bytes
+    /// ML-DSA public key size (1312 or 1952 bytes)
+    pub key_len: u16,                     // 2 bytes
+    /// PDA bump seed
+    pub bump: u8,                         // 1 byte
+    /// Emergency freeze flag (0 / 1)
+    pub is_frozen: u8,                    // 1 byte
+    /// Set once the full key is on-chain; unsealed identities are unusable (0 / 1)
+    pub is_sealed: u8,                    // 1 byte
+    /// Account layout version (`QUANTUM_IDENTITY_VERSION`)
+    pub version: u8,                      // 1 byte
+    /// Closed with the PQC key: a header-only tombstone, or a re-registration
+    /// that is not sealed yet (0 / 1)
+    pub is_revoked: u8,                   // 1 byte
+    /// Tombstone left by `migrate_authority`; the new authority follows the
+    /// header (`FORWARD_AUTHORITY_OFFSET`) (0 / 1)
+    pub is_forwarded: u8,                 // 1 byte
+    /// Number of `MintPolicy` PDAs, so `migrate_authority` can require all of them
+    pub mint_policy_count: u16,           // 2 bytes
+    /// Reserved for future flags (keeps the header 8-byte aligned)
+    pub reserved: [u8; 2],                // 2 bytes
+}
+
+impl QuantumIdentity {
+    /// Discriminator plus header; the public key starts here
+    pub const HEADER_SPACE: usize = 8 + std::mem::size_of::<QuantumIdentity>();
+
+    /// Account size for a `key_len`-byte public key
+    pub const fn space(key_len: usize) -> usize {
+        Self::HEADER_SPACE + key_len
+    }
+}
+
+// The hook reads identities raw through `quresis_common::QuantumIdentityHeader`.
+// Fail the build if the shared header disagrees with the zero-copy struct on
+// any field offset, the header length or the discriminator.
+const _: () = {
+    use core::mem::offset_of;
+    use quresis_common as layout;
+
+    assert!(layout::bytes_eq(
+        <QuantumIdentity as anchor_lang::Discriminator>::DISCRIMINATOR,
+        &layout::QUANTUM_IDENTITY_DISCRIMINATOR,
+    ));
+    assert!(layout::AUTHORITY_OFFSET == 8 + offset_of!(QuantumIdentity, authority));
+    assert!(layout::SEQUENCE_OFFSET == 8 + offset_of!(QuantumIdentity, sequence));
+    assert!(layout::LAST_ACTIVE_SLOT_OFFSET == 8 + offset_of!(QuantumIdentity, last_active_slot));
+    assert!(layout::CREATED_AT_OFFSET == 8 + offset_of!(QuantumIdentity, created_at));
+    assert!(layout::THRESHOLD_OFFSET == 8 + offset_of!(QuantumIdentity, threshold_amount));
+    assert!(layout::WINDOW_START_OFFSET == 8 + offset_of!(QuantumIdentity, current_window_start));
+    assert!(layout::WINDOW_AMOUNT_OFFSET == 8 + offset_of!(QuantumIdentity, current_window_amount));
+    assert!(layout::KEY_VERSION_OFFSET == 8 + offset_of!(QuantumIdentity, key_version));
+    assert!(layout::KEY_WRITTEN_LEN_OFFSET == 8 + offset_of!(QuantumIdentity, key_written_len));
+    assert!(layout::KEY_LEN_OFFSET == 8 + offset_of!(QuantumIdentity, key_len));
+    assert!(layout::BUMP_OFFSET == 8 + offset_of!(QuantumIdentity, bump));
+    assert!(layout::IS_FROZEN_OFFSET == 8 + offset_of!(QuantumIdentity, is_frozen));
+    assert!(layout::IS_SEALED_OFFSET == 8 + offset_of!(QuantumIdentity, is_sealed));
+    assert!(layout::VERSION_OFFSET == 8 + offset_of!(QuantumIdentity, version));
+    assert!(layout::IS_REVOKED_OFFSET == 8 + offset_of!(QuantumIdentity, is_revoked));
+    assert!(layout::IS_FORWARDED_OFFSET == 8 + offset_of!(QuantumIdentity, is_forwarded));
+    assert!(layout::MINT_POLICY_COUNT_OFFSET == 8 + offset_of!(QuantumIdentity, mint_policy_count));
+    assert!(layout::QUANTUM_IDENTITY_HEADER_LEN == QuantumIdentity::HEADER_SPACE);
+};
+
+/// `QuantumIdentity` as deployed before it became zero-copy
+/// (`QUANTUM_IDENTITY_VERSION_BORSH`); only read by `migrate_identity`.
+/// Registration was a single instruction then, so every such key is complete.
+#[derive(AnchorDeserialize)]
+struct QuantumIdentityBorsh {
+    authority: Pubkey,
+    bump: u8,
+    sequence: u64,
+    last_active_slot: u64,
+    created_at: i64,
+    is_frozen: bool,
+    threshold_amount: u64,
+    key_version: u16,
+    current_window_start: i64,
+    current_window_amount: u64,
+    pqc_public_key: Vec<u8>,
+}
+
+/// Staging area for an ML-DSA signature (or a replacement public key)
+/// uploaded across several transactions.
+#[account]
+pub struct SignatureBuffer {
+    /// Wallet that created the buffer; the only one allowed to write or close it
+    pub authority: Pubkey,                // 32 bytes
+    /// Caller-chosen id (part of the PDA seeds)
+    pub buffer_id: u64,                   // 8 bytes
+    /// PDA bump seed
+    pub bump: u8,                         // 1 byte
+    /// Final data length (a signature or public key size)
+    pub expected_len: u32,                // 4 bytes
+    /// High-water mark of contiguous bytes written
+    pub written_len: u32,                 // 4 bytes
+    /// Once set, the data is immutable and usable for verification
+    pub is_finalized: bool,               // 1 byte
+    /// Staged bytes (preallocated to `expected_len`)
+    pub data: Vec<u8>,                    // 4 + len bytes
+}
+
+impl SignatureBuffer {
+    /// Header space up to and including the vector's length prefix; the
+    /// data itself is sized per buffer by `init_buffer` (`data_len`)
+    pub const INIT_SPACE: usize = 32 + 8 + 1 + 4 + 4 + 1 + 4;
+}
+
+/// Intermediate state of an ML-DSA verification spread over several
+/// transactions (see `ml_dsa::resumable`).
+#[account]
+#[derive(InitSpace)]
+pub struct VerificationSession {
+    /// Wallet that opened the session; receives the rent on close
+    pub authority: Pubkey,                // 32 bytes
+    /// QuantumIdentity whose key is being checked
+    pub identity: Pubkey,                 // 32 bytes
+    /// Key version at start; a rotation invalidates the session
+    pub key_version: u16,                 // 2 bytes
+    /// Caller-chosen id (part of the PDA seeds)
+    pub session_id: u64,                  // 8 bytes
+    /// PDA bump seed
+    pub bump: u8,                         // 1 byte
+    /// Current state; `Verified` / `Rejected` are final
+    pub status: VerificationStatus,       // 1 byte
+    /// Next step to run: 0 = NTT(z), i = row i-1 of A
+    pub next_step: u8,                    // 1 byte
+    /// Last slot at which the session can advance or be consumed
+    pub expires_at_slot: u64,             // 8 bytes
+    /// `hash_message` of the signed message, matched by consumers
+    pub message_hash: [u8; 32],           // 32 bytes
+    /// Serialized SHAKE256 challenge hasher
+    pub hasher_state: [u8; 201],          // 201 bytes
+    /// Commitment hash c̃ from the signature
+    #[max_len(48)]
+    pub c_tilde: Vec<u8>,                 // 4 + len bytes
+    /// Packed hint from the signature
+    #[max_len(84)]
+    pub hint: Vec<u8>,                    // 4 + len bytes
+    /// z (NTT domain after step 0), l × 256 coefficients
+    #[max_len(1280)]
+    pub z_hat: Vec<u32>,                  // 4 + 4 * len bytes
+    /// NTT(SampleInBall(c̃)), filled in by step 0
+    #[max_len(256)]
+    pub c_hat: Vec<u32>,                  // 4 + 4 * len bytes
+}
+
+/// Single-use permission for one high-value transfer, minted by
+/// `verify_signature` and consumed by the transfer hook.
+#[account]
+#[derive(InitSpace)]
+pub struct TransferAuthorization {
+    /// QuantumIdentity that signed the authorization
+    pub identity: Pubkey,                 // 32 bytes
+    /// Token-2022 mint the transfer must be of
+    pub mint: Pubkey,                     // 32 bytes
+    /// Destination token account the transfer must go to
+    pub destination: Pubkey,              // 32 bytes
+    /// Largest amount (raw units) the transfer may move
+    pub amount_cap: u64,                  // 8 bytes
+    /// Last slot at which the receipt can be consumed
+    pub expires_at_slot: u64,             // 8 bytes
+    /// Identity sequence of the signed transfer message
+    pub sequence: u64,                    // 8 bytes
+    /// Paid the receipt's rent; the only account it can be refunded to
+    pub payer: Pubkey,                    // 32 bytes
+    /// Set by the transfer that spent the receipt
+    pub consumed: bool,                   // 1 byte
+    /// PDA bump seed
+    pub bump: u8,                         // 1 byte
+}
+
+impl TransferAuthorization {
+    /// Account size before `payer` and `consumed` were added
+    pub const LEGACY_LEN: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 1;
+}
+
+/// 24h velocity window of one identity on one mint, kept by `record_transfer`
+/// so transfers of one mint cannot use up the allowance of another. Read raw
+/// by the hook through `quresis_common::VelocityStateData`.
+#[account]
+#[derive(InitSpace)]
+pub struct VelocityState {
+    /// QuantumIdentity whose transfers are counted
+    pub identity: Pubkey,                 // 32 bytes
+    /// Token-2022 mint the window counts
+    pub mint: Pubkey,                     // 32 bytes
+    /// Start of the current 24h window
+    pub window_start: i64,                // 8 bytes
+    /// Amount sent in the current window, in threshold units
+    pub window_amount: u64,               // 8 bytes
+    /// PDA bump seed
+    pub bump: u8,                         // 1 byte
+}
+
+// Borsh stores these fixed-size fields back to back, which is the layout the
+// hook reads; fail the build if the discriminator or the size drift.
+const _: () = {
+    assert!(quresis_common::bytes_eq(
+        <VelocityState as anchor_lang::Discriminator>::DISCRIMINATOR,
+        &quresis_common::VELOCITY_STATE_DISCRIMINATOR,
+    ));
+    assert!(8 + VelocityState::INIT_SPACE == quresis_common::VELOCITY_STATE_LEN);
+};
+
+/// Per-mint override of an identity's threshold, set with
+/// `create_mint_policy`. The hook reads it raw through
+/// `quresis_common::MintPolicyData` and prefers it over `threshold_amount`.
+#[account]
+#[derive(InitSpace)]
+pub struct MintPolicy {
+    /// QuantumIdentity the policy belongs to
+    pub identity: Pubkey,                 // 32 bytes
+    /// Token-2022 mint the policy applies to
+    pub mint: Pubkey,                     // 32 bytes
+    /// Single transfer amount requiring PQC, in threshold units
+    pub threshold_amount: u64,            // 8 bytes
+    /// 24h total on this mint requiring PQC, in threshold units
+    pub velocity_limit: u64,              // 8 bytes
+    /// PDA bump seed
+    pub bump: u8,                         // 1 byte
+}
+
+const _: () = {
+    assert!(quresis_common::bytes_eq(
+        <MintPolicy as anchor_lang::Discriminator>::DISCRIMINATOR,
+        &quresis_common::MINT_POLICY_DISCRIMINATOR,
+    ));
+    assert!(8 + MintPolicy::INIT_SPACE == quresis_common::MINT_POLICY_LEN);
+};
+
+/// Registry entry: a hook program allowed to update identities via CPI
+#[account]
+#[derive(InitSpace)]
+pub struct ApprovedHook {
+    /// The approved hook program
+    pub hook_program: Pubkey,             // 32 bytes
+    /// Its `[HOOK_CALLER_SEED]` signer PDA, which must sign every CPI
+    pub caller: Pubkey,                   // 32 bytes
+    /// Upgrade authority that approved it
+    pub approved_by: Pubkey,              // 32 bytes
+    /// PDA bump seed
+    pub bump: u8,                         // 1 byte
+}
+
+/// What a `verify_signature` call authorizes (see `TransferAuthorization`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TransferAuthorizationParams {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount_cap: u64,
+    pub expires_at_slot: u64,
+}
+
+// The hasher field must hold exactly one exported sponge.
+const _: () = assert!(ml_dsa::resumable::HASHER_STATE_LEN == 201);
+
+/// Lifecycle of a VerificationSession
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum VerificationStatus {
+    /// Steps remain to be run
+    InProgress,
+    /// The signature is valid; can be consumed once
+    Verified,
+    /// The signature is invalid or malformed
+    Rejected,
+    /// A verified session that has already been used
+    Consumed,
+}
+
+// ============================================================================
+// EVENTS
+// ============================================================================
+
+#[event]
+pub struct IdentityRegistered {
+    pub authority: Pubkey,
+    pub key_size: u16,
+    pub threshold: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct KeyRotated {
+    pub authority: Pubkey,
+    pub old_version: u16,
+    pub new_version: u16,
+    pub new_key_size: u16,
+    pub slot: u64,
+}
+
+#[event]
+pub struct SignatureVerified {
+    pub authority: Pubkey,
+    pub message_hash: [u8; 32],
+    pub sequence: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct TransferAuthorized {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount_cap: u64,
+    pub expires_at_slot: u64,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct TransferAuthorizationConsumed {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub sequence: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct HookApprovalChanged {
+    pub hook_program: Pubkey,
+    pub approved: bool,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct VerificationCompleted {
+    pub identity: Pubkey,
+    pub session_id: u64,
+    pub verified: bool,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ThresholdUpdated {
+    pub authority: Pubkey,
+    pub old_threshold: u64,
+    pub new_threshold: u64,
+}
+
+#[event]
+pub struct MintPolicyChanged {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub threshold_amount: u64,
+    pub velocity_limit: u64,
+    pub removed: bool,
+}
+
+#[event]
+pub struct FreezeToggled {
+    pub authority: Pubkey,
+    pub is_frozen: bool,
+    pub slot: u64,
+}
+
+#[event]
+pub struct IdentityMigrated {
+    pub authority: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[event]
+pub struct IdentityRevoked {
+    pub authority: Pubkey,
+    pub key_version: u16,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AuthorityMigrated {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub key_version: u16,
+    pub slot: u64,
+}
+
+// ============================================================================
+// ERRORS
+// ============================================================================
+
+#[error_code]
+pub enum QuresisError {
+    #[msg("Invalid PQC public key length. Expected 1312 (ML-DSA-44) or 1952 (ML-DSA-65) bytes.")]
+    InvalidKeyLength,
+
+    #[msg("Quantum signature verification failed.")]
+    InvalidQuantumSignature,
 
     #[msg("This Quantum Identity is currently frozen.")]
     IdentityFrozen,
@@ -449,38 +2593,635 @@ pub enum QuresisError {
     #[msg("Sequence number mismatch - possible replay attack.")]
     SequenceMismatch,
 
-    #[msg("Invalid threshold: must be between 1 SOL and 1,000,000 SOL.")]
+    #[msg("Invalid threshold: must be between 10^9 and 10^18 units (1 to 1,000,000,000 tokens).")]
     InvalidThreshold,
+
+    #[msg("Signature buffer is already finalized and can no longer be written.")]
+    BufferAlreadyFinalized,
+
+    #[msg("Signature buffer must be finalized before it can be used.")]
+    BufferNotFinalized,
+
+    #[msg("Signature buffer is incomplete: not every byte has been written.")]
+    BufferIncomplete,
+
+    #[msg("Chunk is out of bounds or would leave a gap in the buffer.")]
+    ChunkOutOfBounds,
+
+    #[msg("Provide the signature either inline or via a buffer, not both.")]
+    AmbiguousSignatureSource,
+
+    #[msg("Provide the new key either inline or via a buffer, not both.")]
+    AmbiguousKeySource,
+
+    #[msg("This Quantum Identity has not been sealed yet.")]
+    IdentityNotSealed,
+
+    #[msg("This Quantum Identity is already sealed.")]
+    IdentityAlreadySealed,
+
+    #[msg("PQC public key upload is incomplete: not every byte has been written.")]
+    KeyIncomplete,
+
+    #[msg("Signature buffer belongs to a different authority.")]
+    BufferAuthorityMismatch,
+
+    #[msg("Proof of possession failed: the new key did not sign the binding message.")]
+    InvalidPossessionProof,
+
+    #[msg("Verification session has already completed.")]
+    SessionNotInProgress,
+
+    #[msg("Verification session has expired.")]
+    SessionExpired,
+
+    #[msg("Verification session does not match this identity or key version.")]
+    SessionIdentityMismatch,
+
+    #[msg("Verification session did not verify this message.")]
+    SessionNotVerified,
+
+    #[msg("Pass both the authorization parameters and the TransferAuthorization account, or neither.")]
+    MissingTransferAuthorization,
+
+    #[msg("Message is not the transfer message for the requested authorization.")]
+    TransferMessageMismatch,
+
+    #[msg("Transfer authorization has expired.")]
+    AuthorizationExpired,
+
+    #[msg("Transfer goes to a different destination than authorized.")]
+    AuthorizationDestinationMismatch,
+
+    #[msg("Transfer amount exceeds the authorized cap.")]
+    AuthorizationAmountExceeded,
+
+    #[msg("Transfer authorization has already been used.")]
+    AuthorizationConsumed,
+
+    #[msg("Transfer authorization is unused and unexpired; only its authority can revoke it.")]
+    AuthorizationActive,
+
+    #[msg("Rent can only be refunded to the account that paid for the receipt.")]
+    RentReceiverMismatch,
+
+    #[msg("Caller is not an approved hook program.")]
+    UnauthorizedCaller,
+
+    #[msg("Only the program upgrade authority can manage the hook registry.")]
+    UnauthorizedAdmin,
+
+    #[msg("Identity uses an older account layout; call migrate_identity first.")]
+    IdentityNeedsMigration,
+
+    #[msg("Identity account layout version is not supported by this program.")]
+    UnsupportedIdentityVersion,
+
+    #[msg("Identity already uses the current account layout.")]
+    IdentityUpToDate,
+
+    #[msg("Identity has been revoked; re-register it with reopen_identity.")]
+    IdentityRevoked,
+
+    #[msg("Identity is not a revoked tombstone.")]
+    IdentityNotRevoked,
+
+    #[msg("Identity is not frozen.")]
+    IdentityNotFrozen,
+
+    #[msg("Identity moved to a new authority and cannot be re-registered.")]
+    IdentityForwarded,
+
+    #[msg("Revoked identity cannot be re-registered until the cooldown has passed.")]
+    ReregistrationCooldown,
+
+    #[msg("Message belongs to a reserved Quresis signing domain and cannot be verified here.")]
+    ReservedMessageDomain,
+
+    #[msg("A transfer message without a TransferAuthorization needs the identity authority's signature.")]
+    TransferNeedsAuthority,
+
+    #[msg("Identity has too many mint policies.")]
+    TooManyMintPolicies,
+
+    #[msg("Pass every MintPolicy of the identity, each followed by its address under the new identity.")]
+    MintPolicyCountMismatch,
+
+    #[msg("Account is not a MintPolicy of this identity, or not its address under the new identity.")]
+    MintPolicyMismatch,
 }
 
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
 
-/// Mock verification function for native PQC syscall
-/// TODO: Replace with actual syscall when available
+/// Whether a PQC public key length is accepted for registration/rotation.
+/// The 32-byte mock key is only accepted in `mock-pqc` test builds.
+fn is_supported_key_length(len: usize) -> bool {
+    len == ML_DSA_44_PUBKEY_SIZE
+        || len == ML_DSA_65_PUBKEY_SIZE
+        || (cfg!(feature = "mock-pqc") && len == MOCK_PQC_PUBKEY_SIZE)
+}
+
+/// Pick the signature bytes for an instruction: inline, or from a finalized
+/// SignatureBuffer when one is passed (the inline argument must then be empty).
+fn resolve_signature<'a>(
+    inline: &'a [u8],
+    buffer: &'a Option<Account<SignatureBuffer>>,
+) -> Result<&'a [u8]> {
+    match buffer {
+        Some(buffer) => {
+            require!(inline.is_empty(), QuresisError::AmbiguousSignatureSource);
+            require!(buffer.is_finalized, QuresisError::BufferNotFinalized);
+            Ok(&buffer.data)
+        }
+        None => Ok(inline),
+    }
+}
+
+/// Pick the new public key for `rotate_key`: inline, or from a finalized
+/// SignatureBuffer owned by the same authority.
+fn resolve_new_key(
+    inline: Vec<u8>,
+    buffer: &Option<Account<SignatureBuffer>>,
+    authority: &Pubkey,
+) -> Result<Vec<u8>> {
+    match buffer {
+        Some(buffer) => {
+            require!(inline.is_empty(), QuresisError::AmbiguousKeySource);
+            require!(buffer.is_finalized, QuresisError::BufferNotFinalized);
+            require_keys_eq!(
+                buffer.authority,
+                *authority,
+                QuresisError::BufferAuthorityMismatch
+            );
+            Ok(buffer.data.clone())
+        }
+        None => Ok(inline),
+    }
+}
+
+/// Populate a freshly allocated identity. The caller decides whether it is sealed.
+fn init_identity(
+    identity: &mut QuantumIdentity,
+    authority: Pubkey,
+    bump: u8,
+    key_len: u16,
+    threshold: u64,
+    clock: &Clock,
+) {
+    identity.authority = authority;
+    identity.key_len = key_len;
+    identity.bump = bump;
+    identity.sequence = 0;
+    identity.last_active_slot = clock.slot;
+    identity.created_at = clock.unix_timestamp;
+    identity.is_frozen = 0;
+    identity.is_revoked = 0;
+    identity.is_forwarded = 0;
+    identity.threshold_amount = threshold;
+    identity.key_version = 1;
+    identity.version = QUANTUM_IDENTITY_VERSION;
+}
+
+/// Load an identity's header, rejecting layout versions this build cannot
+/// read (`migrate_identity` upgrades older ones). Every context that takes an
+/// existing identity calls it for the `bump` constraint, so handlers only
+/// ever see the current layout.
+fn load_identity<'a>(
+    identity: &'a AccountLoader<QuantumIdentity>,
+) -> Result<Ref<'a, QuantumIdentity>> {
+    // Read the version from the raw data: a Borsh account has no version
+    // byte, and what sits at that offset is part of its key
+    match quantum_identity_version(&identity.as_ref().try_borrow_data()?) {
+        Some(QUANTUM_IDENTITY_VERSION) => identity.load(),
+        Some(QUANTUM_IDENTITY_VERSION_BORSH) => err!(QuresisError::IdentityNeedsMigration),
+        _ => err!(QuresisError::UnsupportedIdentityVersion),
+    }
+}
+
+/// The `key_len`-byte public key stored after an identity's header.
+/// Drop the borrow before calling `load_mut` on the same account.
+fn identity_key<'a>(
+    identity: &'a AccountLoader<QuantumIdentity>,
+    key_len: u16,
+) -> Result<Ref<'a, [u8]>> {
+    let key_end = QuantumIdentity::space(key_len as usize);
+    let data = identity.as_ref().try_borrow_data()?;
+    require!(data.len() >= key_end, QuresisError::InvalidKeyLength);
+    Ok(Ref::map(data, |data| &data[QuantumIdentity::HEADER_SPACE..key_end]))
+}
+
+/// Mutable counterpart of `identity_key`.
+fn identity_key_mut<'a>(
+    identity: &'a AccountLoader<QuantumIdentity>,
+    key_len: u16,
+) -> Result<RefMut<'a, [u8]>> {
+    let key_end = QuantumIdentity::space(key_len as usize);
+    let data = identity.as_ref().try_borrow_mut_data()?;
+    require!(data.len() >= key_end, QuresisError::InvalidKeyLength);
+    Ok(RefMut::map(data, |data| &mut data[QuantumIdentity::HEADER_SPACE..key_end]))
+}
+
+/// Resize an identity to `new_space` bytes, topping up rent from `payer`
+/// or refunding the surplus to it.
+fn resize_identity<'info>(
+    identity: &AccountLoader<'info, QuantumIdentity>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    new_space: usize,
+) -> Result<()> {
+    let identity = identity.to_account_info();
+    let required = Rent::get()?.minimum_balance(new_space);
+    let current = identity.lamports();
+
+    if required > current {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: identity.clone(),
+                },
+            ),
+            required - current,
+        )?;
+    } else if current > required {
+        identity.sub_lamports(current - required)?;
+        payer.add_lamports(current - required)?;
+    }
+
+    identity.resize(new_space)?;
+    Ok(())
+}
+
+/// Create a `space`-byte PDA of this program paid by `payer`. Done by hand
+/// rather than with `init_if_needed`: a `VelocityState` must wait for the
+/// caller registry constraints, and `migrate_authority` creates `MintPolicy`
+/// PDAs from its remaining accounts. Lamports someone already sent to the
+/// address cannot block the creation.
+fn create_pda<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    seeds: &[&[u8]],
+    space: usize,
+) -> Result<()> {
+    use anchor_lang::system_program::{
+        allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+    };
+
+    let required = Rent::get()?.minimum_balance(space);
+    let current = account.lamports();
+    let signer_seeds = &[seeds];
+    let system_program = system_program.to_account_info();
+
+    if current == 0 {
+        return create_account(
+            CpiContext::new_with_signer(
+                system_program,
+                CreateAccount {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+                signer_seeds,
+            ),
+            required,
+            space as u64,
+            &crate::ID,
+        );
+    }
+
+    if required > current {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            required - current,
+        )?;
+    }
+    allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate {
+                account_to_allocate: account.clone(),
+            },
+            signer_seeds,
+        ),
+        space as u64,
+    )?;
+    assign(
+        CpiContext::new_with_signer(
+            system_program,
+            Assign {
+                account_to_assign: account.clone(),
+            },
+            signer_seeds,
+        ),
+        &crate::ID,
+    )
+}
+
+/// Re-create `old_policy`, a `MintPolicy` of `old_identity`, at its PDA
+/// `new_policy` under `new_identity`, then close it into `payer`.
+fn move_mint_policy<'info>(
+    old_policy: &AccountInfo<'info>,
+    new_policy: &AccountInfo<'info>,
+    old_identity: &Pubkey,
+    new_identity: &Pubkey,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    require_keys_eq!(*old_policy.owner, crate::ID, QuresisError::MintPolicyMismatch);
+    let policy = MintPolicy::try_deserialize(&mut &old_policy.try_borrow_data()?[..])?;
+    require_keys_eq!(policy.identity, *old_identity, QuresisError::MintPolicyMismatch);
+
+    let (address, bump) = Pubkey::find_program_address(
+        &[MINT_POLICY_SEED, new_identity.as_ref(), policy.mint.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(new_policy.key(), address, QuresisError::MintPolicyMismatch);
+    create_pda(
+        new_policy,
+        payer,
+        system_program,
+        &[MINT_POLICY_SEED, new_identity.as_ref(), policy.mint.as_ref(), &[bump]],
+        8 + MintPolicy::INIT_SPACE,
+    )?;
+    MintPolicy {
+        identity: *new_identity,
+        bump,
+        ..policy
+    }
+    .try_serialize(&mut &mut new_policy.try_borrow_mut_data()?[..])?;
+
+    payer.add_lamports(old_policy.lamports())?;
+    old_policy.sub_lamports(old_policy.lamports())?;
+    old_policy.assign(&System::id());
+    old_policy.resize(0)?;
+    Ok(())
+}
+
+/// Proof of possession: `signature` must be `public_key`'s signature over
+/// `KEY_POSSESSION_DOMAIN || program_id || authority`. Binding the wallet and
+/// the program stops key squatting and cross-deployment replay of the proof.
+fn require_key_possession(public_key: &[u8], authority: &Pubkey, signature: &[u8]) -> Result<()> {
+    let mut message = Vec::with_capacity(KEY_POSSESSION_DOMAIN.len() + 64);
+    message.extend_from_slice(KEY_POSSESSION_DOMAIN);
+    message.extend_from_slice(crate::ID.as_ref());
+    message.extend_from_slice(authority.as_ref());
+
+    require_valid_pqc_signature(public_key, &message, signature)
+        .map_err(|_| error!(QuresisError::InvalidPossessionProof))
+}
+
+/// `KEY_ROTATION_DOMAIN || sequence (u64 LE) || new_key`, the message the old
+/// key signs to authorize a rotation.
+fn rotation_message(sequence: u64, new_key: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(KEY_ROTATION_DOMAIN.len() + 8 + new_key.len());
+    message.extend_from_slice(KEY_ROTATION_DOMAIN);
+    message.extend_from_slice(&sequence.to_le_bytes());
+    message.extend_from_slice(new_key);
+    message
+}
+
+/// `domain || authority || payload || sequence (u64 LE)`, the message a key
+/// signs to authorize an action on its identity (`close_identity`,
+/// `freeze_with_pqc`, `unfreeze`, raising the threshold, loosening a mint
+/// policy, `migrate_authority`).
+fn identity_action_message(
+    domain: &[u8],
+    authority: &Pubkey,
+    payload: &[u8],
+    sequence: u64,
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(domain.len() + 32 + payload.len() + 8);
+    message.extend_from_slice(domain);
+    message.extend_from_slice(authority.as_ref());
+    message.extend_from_slice(payload);
+    message.extend_from_slice(&sequence.to_le_bytes());
+    message
+}
+
+/// Validate a mint policy change from `old` to `new` (threshold, velocity
+/// limit), or to the identity threshold when `new` is `None` (deletion).
+/// Like `update_threshold`, only raising a value needs the key's signature,
+/// which then advances the identity's sequence.
+fn authorize_mint_policy_change(
+    identity: &AccountLoader<QuantumIdentity>,
+    mint: &Pubkey,
+    old: (u64, u64),
+    new: Option<(u64, u64)>,
+    signature: &[u8],
+) -> Result<()> {
+    let identity_threshold = {
+        let header = identity.load()?;
+        require!(header.is_sealed != 0, QuresisError::IdentityNotSealed);
+        header.threshold_amount
+    };
+
+    let (threshold_amount, velocity_limit) = match new {
+        Some((threshold_amount, velocity_limit)) => {
+            for value in [threshold_amount, velocity_limit] {
+                require!(
+                    (MIN_THRESHOLD..=MAX_THRESHOLD).contains(&value),
+                    QuresisError::InvalidThreshold
+                );
+            }
+            (threshold_amount, velocity_limit)
+        }
+        None => (identity_threshold, identity_threshold),
+    };
+
+    let loosens = threshold_amount > old.0 || velocity_limit > old.1;
+    if loosens {
+        let mut payload = Vec::with_capacity(32 + 8 + 8);
+        payload.extend_from_slice(mint.as_ref());
+        let domain = match new {
+            Some(_) => {
+                payload.extend_from_slice(&threshold_amount.to_le_bytes());
+                payload.extend_from_slice(&velocity_limit.to_le_bytes());
+                MINT_POLICY_DOMAIN
+            }
+            None => MINT_POLICY_DELETE_DOMAIN,
+        };
+        require_identity_action_signature(identity, domain, &payload, signature)?;
+    }
+
+    let mut header = identity.load_mut()?;
+    if loosens {
+        header.sequence = header.sequence.saturating_add(1);
+    }
+    header.last_active_slot = Clock::get()?.slot;
+
+    Ok(())
+}
+
+/// Check the identity key's signature over the `identity_action_message` for
+/// its current sequence. The caller advances the sequence on success.
+fn require_identity_action_signature(
+    identity: &AccountLoader<QuantumIdentity>,
+    domain: &[u8],
+    payload: &[u8],
+    signature: &[u8],
+) -> Result<()> {
+    let header = identity.load()?;
+    require_valid_pqc_signature(
+        &identity_key(identity, header.key_len)?,
+        &identity_action_message(domain, &header.authority, payload, header.sequence),
+        signature,
+    )
+}
+
+/// `TRANSFER_AUTH_DOMAIN || mint || destination || amount_cap (u64 LE) ||
+/// expires_at_slot (u64 LE) || sequence (u64 LE)`, the message the key signs
+/// to mint a TransferAuthorization.
+fn transfer_authorization_message(sequence: u64, params: &TransferAuthorizationParams) -> Vec<u8> {
+    let mut message = Vec::with_capacity(TRANSFER_AUTH_DOMAIN.len() + 32 + 32 + 8 + 8 + 8);
+    message.extend_from_slice(TRANSFER_AUTH_DOMAIN);
+    message.extend_from_slice(params.mint.as_ref());
+    message.extend_from_slice(params.destination.as_ref());
+    message.extend_from_slice(&params.amount_cap.to_le_bytes());
+    message.extend_from_slice(&params.expires_at_slot.to_le_bytes());
+    message.extend_from_slice(&sequence.to_le_bytes());
+    message
+}
+
+/// Domains of messages a key signs for a specific instruction. They must not
+/// verify through the generic `verify_signature` (transfer messages, which
+/// are meant for it, are not listed; it checks them itself).
+const RESERVED_MESSAGE_DOMAINS: &[&[u8]] = &[
+    KEY_POSSESSION_DOMAIN,
+    KEY_ROTATION_DOMAIN,
+    CLOSE_IDENTITY_DOMAIN,
+    MIGRATE_AUTHORITY_DOMAIN,
+    FREEZE_DOMAIN,
+    UNFREEZE_DOMAIN,
+    THRESHOLD_DOMAIN,
+    MINT_POLICY_DOMAIN,
+    MINT_POLICY_DELETE_DOMAIN,
+];
+
+/// Whether `message` is in the domain of another instruction's signature.
+fn is_reserved_message(message: &[u8]) -> bool {
+    RESERVED_MESSAGE_DOMAINS
+        .iter()
+        .any(|domain| message.starts_with(domain))
+}
+
+/// Anti-replay: a signed message must end with the identity's current
+/// sequence number (u64 LE), like the SDK's `createTransferMessage`.
+fn require_current_sequence(message: &[u8], sequence: u64) -> Result<()> {
+    let suffix = message.len().checked_sub(8).map(|start| &message[start..]);
+    require!(
+        suffix == Some(&sequence.to_le_bytes()[..]),
+        QuresisError::SequenceMismatch
+    );
+    Ok(())
+}
+
+/// Record the final verdict of a VerificationSession and drop the bulky
+/// intermediate state.
+fn complete_verification_session(session: &mut VerificationSession, verified: bool, slot: u64) {
+    session.status = if verified {
+        VerificationStatus::Verified
+    } else {
+        VerificationStatus::Rejected
+    };
+    session.z_hat = Vec::new();
+    session.c_hat = Vec::new();
+
+    emit!(VerificationCompleted {
+        identity: session.identity,
+        session_id: session.session_id,
+        verified,
+        slot,
+    });
+
+    msg!(
+        "{} Verification Session #{}: {}",
+        if verified { "✅" } else { "❌" },
+        session.session_id,
+        if verified { "VERIFIED" } else { "REJECTED" }
+    );
+}
+
+/// Accept a `Verified` session in place of a signature, then mark it consumed
+/// so it cannot authorize anything twice.
+fn consume_verification_session(
+    session: &mut VerificationSession,
+    identity: Pubkey,
+    key_version: u16,
+    message: &[u8],
+) -> Result<()> {
+    require_keys_eq!(
+        session.identity,
+        identity,
+        QuresisError::SessionIdentityMismatch
+    );
+    require!(
+        session.key_version == key_version,
+        QuresisError::SessionIdentityMismatch
+    );
+    require!(
+        Clock::get()?.slot <= session.expires_at_slot,
+        QuresisError::SessionExpired
+    );
+    require!(
+        session.status == VerificationStatus::Verified
+            && session.message_hash == hash_message(message),
+        QuresisError::SessionNotVerified
+    );
+
+    session.status = VerificationStatus::Consumed;
+    Ok(())
+}
+
+/// Verify a PQC signature against a stored key, mapping failures to errors.
+fn require_valid_pqc_signature(pubkey: &[u8], message: &[u8], signature: &[u8]) -> Result<()> {
+    #[cfg(not(feature = "mock-pqc"))]
+    {
+        // Size check first so callers get a precise error for truncated uploads
+        if let Some(params) = ml_dsa::ParameterSet::for_public_key(pubkey) {
+            require!(
+                signature.len() == params.signature_len(),
+                QuresisError::InvalidSignatureSize
+            );
+        }
+        require!(
+            ml_dsa::verify(pubkey, message, signature),
+            QuresisError::InvalidQuantumSignature
+        );
+    }
+
+    #[cfg(feature = "mock-pqc")]
+    require!(
+        mock_pqc_verify(pubkey, message, signature),
+        QuresisError::InvalidQuantumSignature
+    );
+
+    Ok(())
+}
+
+/// Mock verification for local test validators (`--features mock-pqc`).
+/// NEVER enable this feature for a deployed program: it accepts any
+/// signature that does not start with the failure marker [0, 0, 0, 0].
+#[cfg(feature = "mock-pqc")]
 fn mock_pqc_verify(_pubkey: &[u8], _message: &[u8], _signature: &[u8]) -> bool {
-    // --- NATIVE PQC SYSCALL PLACEHOLDER ---
-    // In production, this will be replaced with:
-    // solana_program::pqc::verify_ml_dsa(pubkey, message, signature)
-    //
-    // For testing, we simulate success. To test failure paths,
-    // check if signature starts with [0, 0, 0, 0] (failure marker)
     if _signature.len() >= 4 && _signature[0..4] == [0, 0, 0, 0] {
         return false;
     }
     true
 }
 
-/// Hash a message to 32 bytes for event logging
-/// Uses a proper collision-resistant hash via Pubkey derivation (SHA256-based)
-/// This provides cryptographic correctness for event identification
+/// Hash a message to 32 bytes for event logging and session matching
+/// SHA-256 over a fixed prefix and the message; unlike a PDA seed, which
+/// Solana caps at 32 bytes, this accepts messages of any length
 fn hash_message(message: &[u8]) -> [u8; 32] {
-    // Use Pubkey::find_program_address which internally uses SHA256
-    // We hash the message through the PDA derivation mechanism
-    // The bump is discarded - we only want the deterministic hash output
-    let seeds: &[&[u8]] = &[b"msg_hash", message];
-    let (hash_key, _) = Pubkey::find_program_address(seeds, &crate::ID);
-    hash_key.to_bytes()
+    solana_sha256_hasher::hashv(&[b"msg_hash", message]).to_bytes()
 }
 
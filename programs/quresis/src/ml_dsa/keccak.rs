@@ -0,0 +1,197 @@
+//! Keccak-f[1600] permutation and the SHAKE128 / SHAKE256 extendable-output
+//! functions (FIPS 202) used by ML-DSA for `H` and `G`.
+//!
+//! Solana only exposes a Keccak-256 *hash* syscall, which cannot be used as an
+//! XOF, so the sponge is implemented here directly.
+
+/// Round constants for the iota step.
+const RC: [u64; 24] = [
+    0x0000_0000_0000_0001,
+    0x0000_0000_0000_8082,
+    0x8000_0000_0000_808a,
+    0x8000_0000_8000_8000,
+    0x0000_0000_0000_808b,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8009,
+    0x0000_0000_0000_008a,
+    0x0000_0000_0000_0088,
+    0x0000_0000_8000_8009,
+    0x0000_0000_8000_000a,
+    0x0000_0000_8000_808b,
+    0x8000_0000_0000_008b,
+    0x8000_0000_0000_8089,
+    0x8000_0000_0000_8003,
+    0x8000_0000_0000_8002,
+    0x8000_0000_0000_0080,
+    0x0000_0000_0000_800a,
+    0x8000_0000_8000_000a,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8080,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8008,
+];
+
+/// Rotation offsets for the rho step, in pi-step visiting order.
+const RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+/// Lane visiting order for the pi step.
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// SHAKE128 rate in bytes (1600 - 2 * 128 bits).
+pub const SHAKE128_RATE: usize = 168;
+/// SHAKE256 rate in bytes (1600 - 2 * 256 bits).
+pub const SHAKE256_RATE: usize = 136;
+/// Length of an exported sponge: the 25 state lanes plus the absorb position.
+pub const EXPORTED_STATE_LEN: usize = 25 * 8 + 1;
+
+/// Apply the 24-round Keccak-f[1600] permutation in place.
+pub fn keccak_f1600(state: &mut [u64; 25]) {
+    for rc in RC {
+        // θ
+        let mut c = [0u64; 5];
+        for (x, lane) in c.iter_mut().enumerate() {
+            *lane = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[5 * y + x] ^= d;
+            }
+        }
+
+        // ρ and π
+        let mut carry = state[1];
+        for (&target, &rotation) in PI.iter().zip(RHO.iter()) {
+            let next = state[target];
+            state[target] = carry.rotate_left(rotation);
+            carry = next;
+        }
+
+        // χ
+        for y in 0..5 {
+            let row = [
+                state[5 * y],
+                state[5 * y + 1],
+                state[5 * y + 2],
+                state[5 * y + 3],
+                state[5 * y + 4],
+            ];
+            for x in 0..5 {
+                state[5 * y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+
+        // ι
+        state[0] ^= rc;
+    }
+}
+
+/// Incremental SHAKE sponge: absorb any number of times, then squeeze any
+/// number of times. The first `squeeze` call applies the SHAKE padding.
+#[derive(Clone)]
+pub struct Shake {
+    state: [u64; 25],
+    rate: usize,
+    pos: usize,
+    squeezing: bool,
+}
+
+impl Shake {
+    /// A fresh SHAKE128 instance (`G` in FIPS 204).
+    pub const fn shake128() -> Self {
+        Self::with_rate(SHAKE128_RATE)
+    }
+
+    /// A fresh SHAKE256 instance (`H` in FIPS 204).
+    pub const fn shake256() -> Self {
+        Self::with_rate(SHAKE256_RATE)
+    }
+
+    const fn with_rate(rate: usize) -> Self {
+        Self {
+            state: [0u64; 25],
+            rate,
+            pos: 0,
+            squeezing: false,
+        }
+    }
+
+    /// Serialize an absorbing SHAKE256 sponge so hashing can resume in a
+    /// later transaction (see `resume_shake256`).
+    pub fn export(&self) -> [u8; EXPORTED_STATE_LEN] {
+        debug_assert!(!self.squeezing, "export after squeeze");
+        let mut out = [0u8; EXPORTED_STATE_LEN];
+        for (chunk, lane) in out.chunks_exact_mut(8).zip(self.state.iter()) {
+            chunk.copy_from_slice(&lane.to_le_bytes());
+        }
+        out[EXPORTED_STATE_LEN - 1] = self.pos as u8;
+        out
+    }
+
+    /// Restore a SHAKE256 sponge produced by `export`. Returns `None` if the
+    /// absorb position is out of range.
+    pub fn resume_shake256(bytes: &[u8; EXPORTED_STATE_LEN]) -> Option<Self> {
+        let pos = bytes[EXPORTED_STATE_LEN - 1] as usize;
+        if pos >= SHAKE256_RATE {
+            return None;
+        }
+        let mut sponge = Self::shake256();
+        for (lane, chunk) in sponge.state.iter_mut().zip(bytes.chunks_exact(8)) {
+            *lane = u64::from_le_bytes(chunk.try_into().unwrap_or([0u8; 8]));
+        }
+        sponge.pos = pos;
+        Some(sponge)
+    }
+
+    #[inline(always)]
+    fn xor_byte(&mut self, index: usize, byte: u8) {
+        self.state[index / 8] ^= (byte as u64) << (8 * (index % 8));
+    }
+
+    #[inline(always)]
+    fn read_byte(&self, index: usize) -> u8 {
+        (self.state[index / 8] >> (8 * (index % 8))) as u8
+    }
+
+    /// Absorb `data` into the sponge. Must not be called after squeezing.
+    pub fn absorb(&mut self, data: &[u8]) {
+        debug_assert!(!self.squeezing, "absorb after squeeze");
+        for &byte in data {
+            self.xor_byte(self.pos, byte);
+            self.pos += 1;
+            if self.pos == self.rate {
+                keccak_f1600(&mut self.state);
+                self.pos = 0;
+            }
+        }
+    }
+
+    /// Apply SHAKE domain separation + pad10*1 and switch to squeezing.
+    fn finalize(&mut self) {
+        self.xor_byte(self.pos, 0x1f);
+        self.xor_byte(self.rate - 1, 0x80);
+        keccak_f1600(&mut self.state);
+        self.pos = 0;
+        self.squeezing = true;
+    }
+
+    /// Fill `out` with the next bytes of XOF output.
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        if !self.squeezing {
+            self.finalize();
+        }
+        for byte in out.iter_mut() {
+            if self.pos == self.rate {
+                keccak_f1600(&mut self.state);
+                self.pos = 0;
+            }
+            *byte = self.read_byte(self.pos);
+            self.pos += 1;
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! FIPS 204 ML-DSA signature verification for ML-DSA-44 and ML-DSA-65.
+//!
+//! Self-contained (`core` + `alloc` only, no external crypto crates) so it
+//! builds for the SBF target. The parameter set is selected from the length
+//! of the public key.
+//!
+//! Memory is the binding constraint on-chain (32 KB heap, 4 KB stack frames),
+//! so the matrix `Â` is never stored: each entry is re-sampled and folded into
+//! a single row accumulator. Peak heap use is (l + 2) polynomials of 1 KB.
+//!
+//! Signatures are checked as *pure* ML-DSA with an empty context string,
+//! i.e. `M' = 0x00 || 0x00 || M` (FIPS 204 Algorithm 3), which is what
+//! `ml_dsa44.sign(secretKey, message)` in `@noble/post-quantum` produces.
+
+mod keccak;
+mod packing;
+mod poly;
+pub mod resumable;
+mod sampling;
+#[cfg(test)]
+mod tests;
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::constants::{
+    ML_DSA_44_PUBKEY_SIZE, ML_DSA_44_SIG_SIZE, ML_DSA_65_PUBKEY_SIZE, ML_DSA_65_SIG_SIZE,
+};
+
+pub use keccak::Shake;
+use packing::{Hint, T1_POLY_LEN};
+use poly::{use_hint, Poly, N};
+
+/// Length of the public seed ρ at the start of every public key.
+const SEED_LEN: usize = 32;
+/// Length of `tr` and `μ`.
+const DIGEST_LEN: usize = 64;
+/// Largest `c̃` across the supported parameter sets (λ/4 for ML-DSA-65).
+const MAX_C_TILDE_LEN: usize = 48;
+
+/// One ML-DSA parameter set (FIPS 204 Table 1).
+pub struct ParameterSet {
+    pub name: &'static str,
+    /// Rows of `A`.
+    pub k: usize,
+    /// Columns of `A`.
+    pub l: usize,
+    /// Number of ±1 coefficients in the challenge.
+    pub tau: usize,
+    /// Length of the commitment hash `c̃` in bytes (λ/4).
+    pub c_tilde_len: usize,
+    /// log2(γ1).
+    pub gamma1_bits: u32,
+    /// Low-order rounding range γ2.
+    pub gamma2: u32,
+    /// τ · η.
+    pub beta: u32,
+    /// Maximum number of ones in the hint.
+    pub omega: usize,
+}
+
+pub const ML_DSA_44: ParameterSet = ParameterSet {
+    name: "ML-DSA-44",
+    k: 4,
+    l: 4,
+    tau: 39,
+    c_tilde_len: 32,
+    gamma1_bits: 17,
+    gamma2: (poly::Q - 1) / 88,
+    beta: 78,
+    omega: 80,
+};
+
+pub const ML_DSA_65: ParameterSet = ParameterSet {
+    name: "ML-DSA-65",
+    k: 6,
+    l: 5,
+    tau: 49,
+    c_tilde_len: 48,
+    gamma1_bits: 19,
+    gamma2: (poly::Q - 1) / 32,
+    beta: 196,
+    omega: 55,
+};
+
+impl ParameterSet {
+    /// Select the parameter set matching an encoded public key length.
+    pub fn for_public_key(public_key: &[u8]) -> Option<&'static ParameterSet> {
+        match public_key.len() {
+            ML_DSA_44_PUBKEY_SIZE => Some(&ML_DSA_44),
+            ML_DSA_65_PUBKEY_SIZE => Some(&ML_DSA_65),
+            _ => None,
+        }
+    }
+
+    pub const fn gamma1(&self) -> u32 {
+        1 << self.gamma1_bits
+    }
+
+    /// Bits per packed `w1` coefficient: bitlen((q - 1) / (2γ2) - 1).
+    pub const fn w1_bits(&self) -> usize {
+        if self.gamma2 == (poly::Q - 1) / 88 {
+            6
+        } else {
+            4
+        }
+    }
+
+    /// Bytes per packed `z` polynomial.
+    pub const fn z_poly_len(&self) -> usize {
+        N * (self.gamma1_bits as usize + 1) / 8
+    }
+
+    /// Encoded public key length: ρ || t1.
+    pub const fn public_key_len(&self) -> usize {
+        SEED_LEN + self.k * T1_POLY_LEN
+    }
+
+    /// Encoded signature length: c̃ || z || h.
+    pub const fn signature_len(&self) -> usize {
+        self.c_tilde_len + self.l * self.z_poly_len() + self.omega + self.k
+    }
+}
+
+// The size constants exposed to clients must agree with the parameter sets.
+const _: () = assert!(ML_DSA_44.public_key_len() == ML_DSA_44_PUBKEY_SIZE);
+const _: () = assert!(ML_DSA_44.signature_len() == ML_DSA_44_SIG_SIZE);
+const _: () = assert!(ML_DSA_65.public_key_len() == ML_DSA_65_PUBKEY_SIZE);
+const _: () = assert!(ML_DSA_65.signature_len() == ML_DSA_65_SIG_SIZE);
+
+/// Verify an ML-DSA signature (FIPS 204 Algorithm 3, empty context).
+///
+/// Returns `false` for unsupported key lengths, wrongly-sized or malformed
+/// signatures, and signatures that do not verify.
+pub fn verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let Some(params) = ParameterSet::for_public_key(public_key) else {
+        return false;
+    };
+    if signature.len() != params.signature_len() {
+        return false;
+    }
+    let mu = message_representative(public_key, message);
+    verify_internal(params, public_key, &mu, signature)
+}
+
+/// `μ = H(H(pk, 64) || 0x00 || 0x00 || M, 64)`.
+pub fn message_representative(public_key: &[u8], message: &[u8]) -> [u8; DIGEST_LEN] {
+    let mut tr = [0u8; DIGEST_LEN];
+    let mut h = Shake::shake256();
+    h.absorb(public_key);
+    h.squeeze(&mut tr);
+
+    let mut mu = [0u8; DIGEST_LEN];
+    let mut h = Shake::shake256();
+    h.absorb(&tr);
+    // Pure ML-DSA domain separator followed by a zero-length context string.
+    h.absorb(&[0u8, 0u8]);
+    h.absorb(message);
+    h.squeeze(&mut mu);
+    mu
+}
+
+/// ML-DSA.Verify_internal (FIPS 204 Algorithm 8) over a precomputed `μ`.
+fn verify_internal(
+    params: &ParameterSet,
+    public_key: &[u8],
+    mu: &[u8; DIGEST_LEN],
+    signature: &[u8],
+) -> bool {
+    let (rho, t1) = public_key.split_at(SEED_LEN);
+    let (c_tilde, rest) = signature.split_at(params.c_tilde_len);
+    let (z, h) = rest.split_at(params.l * params.z_poly_len());
+
+    let Some(hint) = Hint::decode(params, h) else {
+        return false;
+    };
+    let Some(z_hat) = decode_z_hat(params, z) else {
+        return false;
+    };
+    let c_hat = challenge_hat(params, c_tilde);
+
+    let mut hasher = Shake::shake256();
+    hasher.absorb(mu);
+
+    let mut scratch = Box::new(Poly::zero());
+    for i in 0..params.k {
+        absorb_w1_row(
+            params,
+            rho,
+            i,
+            &t1[i * T1_POLY_LEN..(i + 1) * T1_POLY_LEN],
+            &z_hat,
+            &c_hat,
+            hint.row(i),
+            &mut scratch,
+            &mut hasher,
+        );
+    }
+
+    let mut expected = [0u8; MAX_C_TILDE_LEN];
+    hasher.squeeze(&mut expected[..params.c_tilde_len]);
+    expected[..params.c_tilde_len] == *c_tilde
+}
+
+/// Decode `z`, enforce ‖z‖∞ < γ1 - β and move each polynomial to the NTT
+/// domain. Returns `None` if the norm bound is violated.
+fn decode_z_hat(params: &ParameterSet, z: &[u8]) -> Option<Vec<Poly>> {
+    let mut z_hat = Vec::with_capacity(params.l);
+    for bytes in z.chunks_exact(params.z_poly_len()) {
+        let mut p = Poly::zero();
+        if !packing::unpack_z(params, bytes, &mut p) {
+            return None;
+        }
+        p.ntt();
+        z_hat.push(p);
+    }
+    Some(z_hat)
+}
+
+/// `NTT(SampleInBall(c̃))`.
+fn challenge_hat(params: &ParameterSet, c_tilde: &[u8]) -> Box<Poly> {
+    let mut c = Box::new(Poly::zero());
+    sampling::sample_in_ball(c_tilde, params.tau, &mut c);
+    c.ntt();
+    c
+}
+
+/// Compute row `i` of `w'_approx = NTT⁻¹(Â ∘ ẑ - ĉ ∘ NTT(t1 · 2^d))`, apply the
+/// hint and absorb `w1Encode(w1'_i)` into the challenge hasher.
+#[allow(clippy::too_many_arguments)]
+fn absorb_w1_row(
+    params: &ParameterSet,
+    rho: &[u8],
+    i: usize,
+    t1_row: &[u8],
+    z_hat: &[Poly],
+    c_hat: &Poly,
+    hint_row: &[u8],
+    acc: &mut Poly,
+    hasher: &mut Shake,
+) {
+    // acc = -ĉ ∘ NTT(t1_i · 2^d)
+    packing::unpack_t1_shifted(t1_row, acc);
+    acc.ntt();
+    acc.pointwise_mul_assign(c_hat);
+    acc.negate();
+
+    // acc += Σ_j Â[i][j] ∘ ẑ_j
+    for (j, z_hat_j) in z_hat.iter().enumerate() {
+        sampling::expand_a_mul_acc(rho, i, j, z_hat_j, acc);
+    }
+    acc.inv_ntt();
+
+    // w1'_i = UseHint(h_i, w'_approx_i), reusing the accumulator in place.
+    let mut hints = hint_row.iter().peekable();
+    for (n, c) in acc.coeffs.iter_mut().enumerate() {
+        let hinted = hints.next_if(|&&pos| pos as usize == n).is_some();
+        *c = use_hint(hinted, *c, params.gamma2);
+    }
+    packing::absorb_w1(params, &acc.coeffs, hasher);
+}
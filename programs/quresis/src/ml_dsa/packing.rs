@@ -0,0 +1,135 @@
+//! Byte encodings of public keys, signatures and `w1` (FIPS 204 §7.1–7.2).
+//!
+//! Every bit-packed coefficient is read/written least-significant bit first.
+
+use super::keccak::Shake;
+use super::poly::{Poly, D, N, Q};
+use super::ParameterSet;
+
+/// Bits per packed `t1` coefficient (bitlen(q - 1) - d).
+pub const T1_BITS: usize = 10;
+/// Bytes per packed `t1` polynomial.
+pub const T1_POLY_LEN: usize = N * T1_BITS / 8;
+
+/// Unpack `N` coefficients of `bits` bits each from `bytes`
+/// (SimpleBitUnpack, FIPS 204 Algorithm 18).
+fn unpack_bits(bytes: &[u8], bits: usize, out: &mut [u32; N]) {
+    debug_assert_eq!(bytes.len(), N * bits / 8);
+    let mask = (1u64 << bits) - 1;
+    let mut acc = 0u64;
+    let mut acc_bits = 0;
+    let mut input = bytes.iter();
+    for coeff in out.iter_mut() {
+        while acc_bits < bits {
+            acc |= (*input.next().unwrap_or(&0) as u64) << acc_bits;
+            acc_bits += 8;
+        }
+        *coeff = (acc & mask) as u32;
+        acc >>= bits;
+        acc_bits -= bits;
+    }
+}
+
+/// Decode one `t1` polynomial and scale it by 2^d, ready for the NTT.
+pub fn unpack_t1_shifted(bytes: &[u8], out: &mut Poly) {
+    unpack_bits(bytes, T1_BITS, &mut out.coeffs);
+    for c in out.coeffs.iter_mut() {
+        // t1 < 2^10, so t1 * 2^13 <= q - 1 and no reduction is needed.
+        *c <<= D;
+    }
+}
+
+/// Decode one `z` polynomial (BitUnpack with a = γ1 - 1, b = γ1).
+///
+/// Returns `false` if any coefficient violates ‖z‖∞ < γ1 - β, in which case
+/// the signature must be rejected.
+pub fn unpack_z(params: &ParameterSet, bytes: &[u8], out: &mut Poly) -> bool {
+    let gamma1 = params.gamma1();
+    let bound = gamma1 - params.beta;
+    unpack_bits(bytes, params.gamma1_bits as usize + 1, &mut out.coeffs);
+    let mut in_range = true;
+    for c in out.coeffs.iter_mut() {
+        // z = γ1 - packed, with packed in [0, 2γ1)
+        let packed = *c;
+        if packed > gamma1 {
+            let magnitude = packed - gamma1;
+            in_range &= magnitude < bound;
+            *c = Q - magnitude;
+        } else {
+            let magnitude = gamma1 - packed;
+            in_range &= magnitude < bound;
+            *c = magnitude;
+        }
+    }
+    in_range
+}
+
+/// Encode one `w1` polynomial and absorb it directly into `sink`
+/// (w1Encode, FIPS 204 Algorithm 28).
+pub fn absorb_w1(params: &ParameterSet, w1: &[u32; N], sink: &mut Shake) {
+    let bits = params.w1_bits();
+    let mut out = [0u8; 32 * 6];
+    let len = N * bits / 8;
+    let mut acc = 0u32;
+    let mut acc_bits = 0;
+    let mut idx = 0;
+    for &c in w1.iter() {
+        acc |= c << acc_bits;
+        acc_bits += bits;
+        while acc_bits >= 8 {
+            out[idx] = acc as u8;
+            idx += 1;
+            acc >>= 8;
+            acc_bits -= 8;
+        }
+    }
+    sink.absorb(&out[..len]);
+}
+
+/// The hint vector `h`, validated but still in its packed form
+/// (HintBitUnpack, FIPS 204 Algorithm 21).
+///
+/// The first ω bytes are the positions of the set bits, row after row in
+/// strictly increasing order; the last k bytes are the cumulative end index of
+/// each row.
+pub struct Hint<'a> {
+    positions: &'a [u8],
+    ends: &'a [u8],
+}
+
+impl<'a> Hint<'a> {
+    /// Validate the encoding. Returns `None` for a malformed hint (⊥).
+    pub fn decode(params: &ParameterSet, bytes: &'a [u8]) -> Option<Self> {
+        if bytes.len() != params.omega + params.k {
+            return None;
+        }
+        let (positions, ends) = bytes.split_at(params.omega);
+
+        let mut index = 0usize;
+        for &end in ends {
+            let end = end as usize;
+            if end < index || end > params.omega {
+                return None;
+            }
+            let first = index;
+            while index < end {
+                if index > first && positions[index - 1] >= positions[index] {
+                    return None;
+                }
+                index += 1;
+            }
+        }
+        // Unused position slots must be zero.
+        if positions[index..].iter().any(|&p| p != 0) {
+            return None;
+        }
+
+        Some(Self { positions, ends })
+    }
+
+    /// The set-bit positions of row `i`, in increasing order.
+    pub fn row(&self, i: usize) -> &'a [u8] {
+        let start = if i == 0 { 0 } else { self.ends[i - 1] as usize };
+        &self.positions[start..self.ends[i] as usize]
+    }
+}
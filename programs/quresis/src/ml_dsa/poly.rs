@@ -0,0 +1,184 @@
+//! Arithmetic over R_q = Z_q[X]/(X^256 + 1): the number-theoretic transform
+//! and the rounding helpers (`Decompose`, `UseHint`) from FIPS 204 §7.4–7.5.
+//!
+//! Coefficients are always kept fully reduced in `[0, q)`.
+
+/// Polynomial degree.
+pub const N: usize = 256;
+/// The ML-DSA modulus q = 2^23 - 2^13 + 1.
+pub const Q: u32 = 8_380_417;
+/// Number of dropped bits from `t` (the `d` parameter).
+pub const D: u32 = 13;
+
+/// 512th primitive root of unity modulo q.
+const ZETA: u64 = 1753;
+/// 256^-1 mod q, applied at the end of the inverse NTT.
+const N_INV: u32 = 8_347_681;
+
+const fn bit_reverse_8(mut x: usize) -> usize {
+    let mut r = 0;
+    let mut i = 0;
+    while i < 8 {
+        r = (r << 1) | (x & 1);
+        x >>= 1;
+        i += 1;
+    }
+    r
+}
+
+const fn pow_mod_q(mut base: u64, mut exp: usize) -> u64 {
+    let mut acc = 1u64;
+    base %= Q as u64;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = acc * base % Q as u64;
+        }
+        base = base * base % Q as u64;
+        exp >>= 1;
+    }
+    acc
+}
+
+/// `zetas[k] = ζ^BitRev8(k) mod q`, computed at compile time.
+const ZETAS: [u32; N] = {
+    let mut table = [0u32; N];
+    let mut k = 0;
+    while k < N {
+        table[k] = pow_mod_q(ZETA, bit_reverse_8(k)) as u32;
+        k += 1;
+    }
+    table
+};
+
+#[inline(always)]
+pub fn add_q(a: u32, b: u32) -> u32 {
+    let s = a + b;
+    if s >= Q {
+        s - Q
+    } else {
+        s
+    }
+}
+
+#[inline(always)]
+pub fn sub_q(a: u32, b: u32) -> u32 {
+    if a >= b {
+        a - b
+    } else {
+        a + Q - b
+    }
+}
+
+#[inline(always)]
+pub fn mul_q(a: u32, b: u32) -> u32 {
+    ((a as u64 * b as u64) % Q as u64) as u32
+}
+
+/// A polynomial in R_q, either in normal or NTT representation.
+#[derive(Clone)]
+pub struct Poly {
+    pub coeffs: [u32; N],
+}
+
+impl Poly {
+    pub const fn zero() -> Self {
+        Self { coeffs: [0u32; N] }
+    }
+
+    /// Copy `N` coefficients out of a flat slice (e.g. persisted account data).
+    pub fn from_coeffs(coeffs: &[u32]) -> Self {
+        let mut p = Self::zero();
+        p.coeffs.copy_from_slice(coeffs);
+        p
+    }
+
+    /// Forward NTT in place (FIPS 204 Algorithm 41).
+    pub fn ntt(&mut self) {
+        let w = &mut self.coeffs;
+        let mut m = 0;
+        let mut len = 128;
+        while len >= 1 {
+            let mut start = 0;
+            while start < N {
+                m += 1;
+                let zeta = ZETAS[m];
+                for j in start..start + len {
+                    let t = mul_q(zeta, w[j + len]);
+                    w[j + len] = sub_q(w[j], t);
+                    w[j] = add_q(w[j], t);
+                }
+                start += 2 * len;
+            }
+            len /= 2;
+        }
+    }
+
+    /// Inverse NTT in place (FIPS 204 Algorithm 42).
+    pub fn inv_ntt(&mut self) {
+        let w = &mut self.coeffs;
+        let mut m = N;
+        let mut len = 1;
+        while len < N {
+            let mut start = 0;
+            while start < N {
+                m -= 1;
+                let zeta = Q - ZETAS[m];
+                for j in start..start + len {
+                    let t = w[j];
+                    w[j] = add_q(t, w[j + len]);
+                    w[j + len] = mul_q(zeta, sub_q(t, w[j + len]));
+                }
+                start += 2 * len;
+            }
+            len *= 2;
+        }
+        for c in w.iter_mut() {
+            *c = mul_q(*c, N_INV);
+        }
+    }
+
+    /// Pointwise product in the NTT domain: `self ∘ other`.
+    pub fn pointwise_mul_assign(&mut self, other: &Poly) {
+        for (a, &b) in self.coeffs.iter_mut().zip(other.coeffs.iter()) {
+            *a = mul_q(*a, b);
+        }
+    }
+
+    /// Negate every coefficient modulo q.
+    pub fn negate(&mut self) {
+        for c in self.coeffs.iter_mut() {
+            *c = sub_q(0, *c);
+        }
+    }
+}
+
+/// Split `r` into `(r1, r0)` with `r = r1 * 2γ2 + r0 mod q`
+/// (FIPS 204 Algorithm 36). `r0` is returned centred in `(-γ2, γ2]`.
+#[inline(always)]
+pub fn decompose(r: u32, gamma2: u32) -> (u32, i32) {
+    let alpha = 2 * gamma2;
+    let mut r0 = (r % alpha) as i32;
+    if r0 > gamma2 as i32 {
+        r0 -= alpha as i32;
+    }
+    let diff = r as i32 - r0;
+    if diff == (Q - 1) as i32 {
+        (0, r0 - 1)
+    } else {
+        ((diff as u32) / alpha, r0)
+    }
+}
+
+/// Recover the high bits of `r` corrected by `hint` (FIPS 204 Algorithm 40).
+#[inline(always)]
+pub fn use_hint(hint: bool, r: u32, gamma2: u32) -> u32 {
+    let m = (Q - 1) / (2 * gamma2);
+    let (r1, r0) = decompose(r, gamma2);
+    if !hint {
+        r1
+    } else if r0 > 0 {
+        (r1 + 1) % m
+    } else {
+        (r1 + m - 1) % m
+    }
+}
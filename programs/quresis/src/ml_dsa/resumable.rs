@@ -0,0 +1,145 @@
+//! ML-DSA verification split into bounded steps, so a verification that does
+//! not fit one transaction's compute budget can be resumed across several.
+//!
+//! The caller persists the intermediate values between steps:
+//!
+//! 1. [`start`]: validate the encoding, decode `z` and absorb `μ` into the
+//!    challenge hasher.
+//! 2. [`transform`]: `NTT(z)` and `ĉ = NTT(SampleInBall(c̃))`.
+//! 3. [`absorb_row`]: once per row of `Â` (k steps).
+//! 4. [`finish`]: compare the recomputed commitment hash with `c̃`.
+//!
+//! Running the steps in order is equivalent to [`super::verify`].
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use super::keccak::EXPORTED_STATE_LEN;
+use super::packing::{self, Hint, T1_POLY_LEN};
+use super::poly::{Poly, N};
+use super::{
+    absorb_w1_row, message_representative, sampling, ParameterSet, Shake, MAX_C_TILDE_LEN,
+    SEED_LEN,
+};
+
+/// Length of the persisted challenge-hasher state.
+pub const HASHER_STATE_LEN: usize = EXPORTED_STATE_LEN;
+
+/// Everything [`start`] extracts from the signature for the later steps.
+pub struct Started {
+    /// Commitment hash `c̃`.
+    pub c_tilde: Vec<u8>,
+    /// Packed (validated) hint `h`.
+    pub hint: Vec<u8>,
+    /// `z`, l polynomials of `N` coefficients, not yet in the NTT domain.
+    pub z: Vec<u32>,
+    /// SHAKE256 state after absorbing `μ`.
+    pub hasher_state: [u8; HASHER_STATE_LEN],
+}
+
+/// Step 1. Returns `None` for wrongly-sized inputs, a malformed hint or
+/// ‖z‖∞ ≥ γ1 - β, in which case the signature is invalid.
+pub fn start(
+    params: &ParameterSet,
+    public_key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> Option<Started> {
+    if public_key.len() != params.public_key_len() || signature.len() != params.signature_len() {
+        return None;
+    }
+    let (c_tilde, rest) = signature.split_at(params.c_tilde_len);
+    let (z_bytes, h) = rest.split_at(params.l * params.z_poly_len());
+    Hint::decode(params, h)?;
+
+    let mut z = Vec::with_capacity(params.l * N);
+    let mut p = Box::new(Poly::zero());
+    for bytes in z_bytes.chunks_exact(params.z_poly_len()) {
+        if !packing::unpack_z(params, bytes, &mut p) {
+            return None;
+        }
+        z.extend_from_slice(&p.coeffs);
+    }
+
+    let mu = message_representative(public_key, message);
+    let mut hasher = Shake::shake256();
+    hasher.absorb(&mu);
+
+    Some(Started {
+        c_tilde: c_tilde.to_vec(),
+        hint: h.to_vec(),
+        z,
+        hasher_state: hasher.export(),
+    })
+}
+
+/// Step 2. Moves `z` to the NTT domain in place and returns `ĉ`.
+pub fn transform(params: &ParameterSet, c_tilde: &[u8], z: &mut [u32]) -> Vec<u32> {
+    for coeffs in z.chunks_exact_mut(N) {
+        let mut p = Box::new(Poly::from_coeffs(coeffs));
+        p.ntt();
+        coeffs.copy_from_slice(&p.coeffs);
+    }
+
+    let mut c = Box::new(Poly::zero());
+    sampling::sample_in_ball(c_tilde, params.tau, &mut c);
+    c.ntt();
+    c.coeffs.to_vec()
+}
+
+/// Step 3, for row `row` of `Â`. Returns `false` if the persisted state is
+/// corrupt (which a caller should treat as a rejection).
+#[allow(clippy::too_many_arguments)]
+pub fn absorb_row(
+    params: &ParameterSet,
+    public_key: &[u8],
+    hint: &[u8],
+    row: usize,
+    z_hat: &[u32],
+    c_hat: &[u32],
+    hasher_state: &mut [u8; HASHER_STATE_LEN],
+) -> bool {
+    let Some(hint) = Hint::decode(params, hint) else {
+        return false;
+    };
+    let Some(mut hasher) = Shake::resume_shake256(hasher_state) else {
+        return false;
+    };
+    if row >= params.k || z_hat.len() != params.l * N || c_hat.len() != N {
+        return false;
+    }
+
+    let z_hat: Vec<Poly> = z_hat.chunks_exact(N).map(Poly::from_coeffs).collect();
+    let c_hat = Box::new(Poly::from_coeffs(c_hat));
+    let (rho, t1) = public_key.split_at(SEED_LEN);
+
+    let mut scratch = Box::new(Poly::zero());
+    absorb_w1_row(
+        params,
+        rho,
+        row,
+        &t1[row * T1_POLY_LEN..(row + 1) * T1_POLY_LEN],
+        &z_hat,
+        &c_hat,
+        hint.row(row),
+        &mut scratch,
+        &mut hasher,
+    );
+
+    *hasher_state = hasher.export();
+    true
+}
+
+/// Step 4. `true` iff the signature is valid.
+pub fn finish(
+    params: &ParameterSet,
+    c_tilde: &[u8],
+    hasher_state: &[u8; HASHER_STATE_LEN],
+) -> bool {
+    let Some(mut hasher) = Shake::resume_shake256(hasher_state) else {
+        return false;
+    };
+    let mut expected = [0u8; MAX_C_TILDE_LEN];
+    hasher.squeeze(&mut expected[..params.c_tilde_len]);
+    expected[..params.c_tilde_len] == *c_tilde
+}
@@ -0,0 +1,57 @@
+//! Pseudorandom sampling: `SampleInBall` and the rows of `Â = ExpandA(ρ)`
+//! (FIPS 204 §7.3).
+
+use super::keccak::{Shake, SHAKE128_RATE};
+use super::poly::{add_q, mul_q, Poly, N, Q};
+
+/// Sample the challenge polynomial `c` with exactly τ coefficients in {-1, 1}
+/// from the commitment hash `c̃` (FIPS 204 Algorithm 29).
+pub fn sample_in_ball(c_tilde: &[u8], tau: usize, out: &mut Poly) {
+    let mut xof = Shake::shake256();
+    xof.absorb(c_tilde);
+
+    let mut sign_bytes = [0u8; 8];
+    xof.squeeze(&mut sign_bytes);
+    let mut signs = u64::from_le_bytes(sign_bytes);
+
+    out.coeffs = [0u32; N];
+    let mut byte = [0u8; 1];
+    for i in (N - tau)..N {
+        let j = loop {
+            xof.squeeze(&mut byte);
+            if (byte[0] as usize) <= i {
+                break byte[0] as usize;
+            }
+        };
+        out.coeffs[i] = out.coeffs[j];
+        out.coeffs[j] = if signs & 1 == 1 { Q - 1 } else { 1 };
+        signs >>= 1;
+    }
+}
+
+/// Compute `acc += Â[row][col] ∘ ẑ` without materialising `Â[row][col]`.
+///
+/// The matrix entry is rejection-sampled from SHAKE128(ρ || col || row)
+/// (RejNTTPoly, FIPS 204 Algorithm 30) and consumed coefficient by coefficient.
+/// Streaming keeps the verifier's heap use independent of k × l.
+pub fn expand_a_mul_acc(rho: &[u8], row: usize, col: usize, z_hat: &Poly, acc: &mut Poly) {
+    let mut xof = Shake::shake128();
+    xof.absorb(rho);
+    xof.absorb(&[col as u8, row as u8]);
+
+    let mut block = [0u8; SHAKE128_RATE];
+    let mut n = 0;
+    while n < N {
+        xof.squeeze(&mut block);
+        for b in block.chunks_exact(3) {
+            let a = (b[0] as u32) | ((b[1] as u32) << 8) | (((b[2] & 0x7f) as u32) << 16);
+            if a < Q {
+                acc.coeffs[n] = add_q(acc.coeffs[n], mul_q(a, z_hat.coeffs[n]));
+                n += 1;
+                if n == N {
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Known-answer tests against signatures produced by OpenSSL (see `kat/`),
+//! run through both the one-shot verifier and the resumable steps that
+//! `VerificationSession` persists between transactions.
+
+use alloc::vec::Vec;
+
+use super::{resumable, verify, ParameterSet, ML_DSA_44, ML_DSA_65};
+
+struct Vector {
+    public_key: Vec<u8>,
+    message: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+fn decode_hex(hex: &str) -> Vec<u8> {
+    assert!(hex.len().is_multiple_of(2), "odd-length hex");
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("invalid hex"))
+        .collect()
+}
+
+/// Parse `key = hex` lines; a blank line ends each vector.
+fn parse_vectors(text: &str) -> Vec<Vector> {
+    let mut vectors = Vec::new();
+    let (mut public_key, mut message, mut signature) = (None, None, None);
+    for line in text.lines().chain(core::iter::once("")) {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.is_empty() {
+            if let (Some(public_key), Some(message), Some(signature)) =
+                (public_key.take(), message.take(), signature.take())
+            {
+                vectors.push(Vector { public_key, message, signature });
+            }
+            continue;
+        }
+        let (key, value) = line.split_once('=').expect("malformed vector line");
+        let value = value.trim();
+        match key.trim() {
+            "pk" => public_key = Some(decode_hex(value)),
+            "msg" => message = Some(decode_hex(value)),
+            "sig" => signature = Some(decode_hex(value)),
+            _ => {}
+        }
+    }
+    vectors
+}
+
+fn vectors(params: &ParameterSet) -> Vec<Vector> {
+    let text = match params.k {
+        4 => include_str!("kat/ml_dsa_44.txt"),
+        _ => include_str!("kat/ml_dsa_65.txt"),
+    };
+    let vectors = parse_vectors(text);
+    assert!(vectors.len() >= 4, "{}: missing vectors", params.name);
+    for v in &vectors {
+        assert_eq!(v.public_key.len(), params.public_key_len());
+        assert_eq!(v.signature.len(), params.signature_len());
+    }
+    vectors
+}
+
+/// The step sequence of `begin_verification` / `advance_verification`.
+fn verify_resumable(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let Some(params) = ParameterSet::for_public_key(public_key) else {
+        return false;
+    };
+    let Some(mut started) = resumable::start(params, public_key, message, signature) else {
+        return false;
+    };
+    let c_hat = resumable::transform(params, &started.c_tilde, &mut started.z);
+    for row in 0..params.k {
+        if !resumable::absorb_row(
+            params,
+            public_key,
+            &started.hint,
+            row,
+            &started.z,
+            &c_hat,
+            &mut started.hasher_state,
+        ) {
+            return false;
+        }
+    }
+    resumable::finish(params, &started.c_tilde, &started.hasher_state)
+}
+
+/// Both verification paths must agree on every input.
+fn check(public_key: &[u8], message: &[u8], signature: &[u8], expected: bool) {
+    assert_eq!(verify(public_key, message, signature), expected, "one-shot");
+    assert_eq!(verify_resumable(public_key, message, signature), expected, "resumable");
+}
+
+fn accepts_valid(params: &ParameterSet) {
+    for v in vectors(params) {
+        check(&v.public_key, &v.message, &v.signature, true);
+    }
+}
+
+fn rejects_tampered(params: &ParameterSet) {
+    let z_start = params.c_tilde_len;
+    let hint_start = params.c_tilde_len + params.l * params.z_poly_len();
+
+    for v in vectors(params) {
+        // One flipped bit in c̃, z and the hint
+        for position in [0, z_start + 17, hint_start + 1] {
+            let mut signature = v.signature.clone();
+            signature[position] ^= 0x01;
+            check(&v.public_key, &v.message, &signature, false);
+        }
+
+        // Another message
+        let mut message = v.message.clone();
+        match message.first_mut() {
+            Some(byte) => *byte ^= 0x80,
+            None => message.push(0),
+        }
+        check(&v.public_key, &message, &v.signature, false);
+
+        // Another key: flip a bit of ρ and of t1
+        for position in [3, 100] {
+            let mut public_key = v.public_key.clone();
+            public_key[position] ^= 0x01;
+            check(&public_key, &v.message, &v.signature, false);
+        }
+
+        // Wrong lengths
+        check(&v.public_key, &v.message, &v.signature[..v.signature.len() - 1], false);
+        check(&v.public_key[..v.public_key.len() - 1], &v.message, &v.signature, false);
+    }
+}
+
+#[test]
+fn ml_dsa_44_accepts_known_answers() {
+    accepts_valid(&ML_DSA_44);
+}
+
+#[test]
+fn ml_dsa_65_accepts_known_answers() {
+    accepts_valid(&ML_DSA_65);
+}
+
+#[test]
+fn ml_dsa_44_rejects_tampered_signatures() {
+    rejects_tampered(&ML_DSA_44);
+}
+
+#[test]
+fn ml_dsa_65_rejects_tampered_signatures() {
+    rejects_tampered(&ML_DSA_65);
+}
+
+#[test]
+fn rejects_signature_of_other_parameter_set() {
+    let v44 = &vectors(&ML_DSA_44)[0];
+    let v65 = &vectors(&ML_DSA_65)[0];
+    check(&v65.public_key, &v44.message, &v44.signature, false);
+    check(&v44.public_key, &v65.message, &v65.signature, false);
+}
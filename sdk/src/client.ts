@@ -1,13 +1,32 @@
 import {
+    AccountMeta,
+    ComputeBudgetProgram,
     Connection,
     PublicKey,
     Transaction,
     TransactionInstruction,
     SystemProgram,
 } from '@solana/web3.js';
+import bs58 from 'bs58';
 import { PROGRAM_IDS, DEFAULTS } from './constants';
-import { deriveIdentityPda, deriveHookConfigPda } from './utils';
-import type { QuantumIdentity, HookConfig, EnforcementMode } from './types';
+import {
+    deriveIdentityPda,
+    deriveHookConfigPda,
+    deriveSignatureBufferPda,
+    deriveVerificationSessionPda,
+    deriveTransferAuthorizationPda,
+    deriveVelocityStatePda,
+    deriveMintPolicyPda,
+} from './utils';
+import type {
+    QuantumIdentity,
+    HookConfig,
+    VelocityState,
+    MintPolicy,
+    EnforcementMode,
+    FailurePolicy,
+    TransferAuthorizationParams,
+} from './types';
 
 /**
  * Configuration for QuresisClient
@@ -47,7 +66,7 @@ export interface QuresisClientConfig {
  * const identity = await client.getIdentity(wallet.publicKey);
  * 
  * // Register a new identity
- * await client.registerIdentity(pqcPublicKey);
+ * await client.registerIdentity(pqcPublicKey, possessionSignature);
  * ```
  */
 export class QuresisClient {
@@ -105,17 +124,19 @@ export class QuresisClient {
      * Build a register identity instruction
      * 
      * @param pqcPublicKey - ML-DSA public key bytes
-     * @param threshold - Optional threshold in lamports
+     * @param possessionSignature - New key's signature over createPossessionMessage
+     * @param threshold - Optional threshold in threshold units (THRESHOLD_DECIMALS)
      * @returns Transaction instruction
      */
     buildRegisterIdentityInstruction(
         pqcPublicKey: Uint8Array,
+        possessionSignature: Uint8Array,
         threshold: bigint = DEFAULTS.threshold
     ): TransactionInstruction {
         const { pda } = this.getIdentityPda(this.wallet.publicKey);
 
         // Anchor instruction discriminator for "register_identity"
-        const discriminator = Buffer.from([175, 176, 141, 171, 210, 183, 107, 119]);
+        const discriminator = Buffer.from([164, 118, 227, 177, 47, 176, 187, 248]);
 
         // Serialize arguments
         const thresholdBuffer = Buffer.alloc(9);
@@ -125,11 +146,16 @@ export class QuresisClient {
         const keyLenBuffer = Buffer.alloc(4);
         keyLenBuffer.writeUInt32LE(pqcPublicKey.length, 0);
 
+        const sigLenBuffer = Buffer.alloc(4);
+        sigLenBuffer.writeUInt32LE(possessionSignature.length, 0);
+
         const data = Buffer.concat([
             discriminator,
             keyLenBuffer,
             Buffer.from(pqcPublicKey),
             thresholdBuffer,
+            sigLenBuffer,
+            Buffer.from(possessionSignature),
         ]);
 
         return new TransactionInstruction({
@@ -138,6 +164,8 @@ export class QuresisClient {
                 { pubkey: pda, isSigner: false, isWritable: true },
                 { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
                 { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+                // signature_buffer: None (program id placeholder)
+                { pubkey: this.quresisProgram, isSigner: false, isWritable: false },
             ],
             data,
         });
@@ -145,16 +173,26 @@ export class QuresisClient {
 
     /**
      * Register a Quantum Identity for the connected wallet
+     *
+     * Keys larger than one chunk (every real ML-DSA key) are registered in
+     * stages: begin_registration → write_key_chunk × n → seal_identity.
      * 
      * @param pqcPublicKey - ML-DSA public key bytes
-     * @param threshold - Optional threshold in lamports (default: 100 SOL)
-     * @returns Transaction signature
+     * @param possessionSignature - New key's signature over
+     *   `QuresisSigner.createPossessionMessage(wallet, quresisProgram)`
+     * @param threshold - Optional threshold in threshold units (default: 100 tokens)
+     * @returns Signature of the final (registering or sealing) transaction
      */
     async registerIdentity(
         pqcPublicKey: Uint8Array,
+        possessionSignature: Uint8Array,
         threshold?: bigint
     ): Promise<string> {
-        const ix = this.buildRegisterIdentityInstruction(pqcPublicKey, threshold);
+        if (pqcPublicKey.length > DEFAULTS.chunkSize) {
+            return this.registerIdentityStaged(pqcPublicKey, possessionSignature, threshold);
+        }
+
+        const ix = this.buildRegisterIdentityInstruction(pqcPublicKey, possessionSignature, threshold);
 
         const tx = new Transaction().add(ix);
         tx.feePayer = this.wallet.publicKey;
@@ -168,58 +206,609 @@ export class QuresisClient {
     }
 
     /**
-     * Update the threshold for quantum signature requirement
-     * 
-     * @param newThreshold - New threshold in lamports
-     * @returns Transaction signature
+     * Register a Quantum Identity by uploading the key in chunks.
+     * The identity cannot be used until the final seal_identity succeeds.
+     * A possession signature larger than one chunk is staged in signature
+     * buffer `bufferId` and the buffer is closed after sealing.
+     *
+     * @param pqcPublicKey - ML-DSA public key bytes (1312 or 1952)
+     * @param possessionSignature - New key's signature over createPossessionMessage
+     * @param threshold - Threshold in threshold units (default: 100 tokens)
+     * @param bufferId - Signature buffer id for the possession signature (default: 0)
+     * @returns Signature of the seal_identity transaction
+     */
+    async registerIdentityStaged(
+        pqcPublicKey: Uint8Array,
+        possessionSignature: Uint8Array,
+        threshold: bigint = DEFAULTS.threshold,
+        bufferId: bigint = BigInt(0)
+    ): Promise<string> {
+        const { pda } = this.getIdentityPda(this.wallet.publicKey);
+
+        // Anchor instruction discriminator for "begin_registration"
+        const beginDisc = Buffer.from([10, 33, 193, 138, 120, 88, 91, 218]);
+
+        const beginArgs = Buffer.alloc(13);
+        beginArgs.writeUInt32LE(pqcPublicKey.length, 0);
+        beginArgs.writeUInt8(1, 4); // Some variant
+        beginArgs.writeBigUInt64LE(threshold, 5);
+
+        await this.sendInstruction(new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: [
+                { pubkey: pda, isSigner: false, isWritable: true },
+                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
+                { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+            ],
+            data: Buffer.concat([beginDisc, beginArgs]),
+        }));
+
+        return this.uploadAndSealKey(pqcPublicKey, possessionSignature, bufferId);
+    }
+
+    /**
+     * Re-register a revoked identity (see closeIdentity) with a new key.
+     * reopen_identity only succeeds once the re-registration cooldown has
+     * passed; the key is then uploaded and sealed as in registerIdentityStaged.
+     *
+     * @param pqcPublicKey - New ML-DSA public key bytes
+     * @param possessionSignature - New key's signature over createPossessionMessage
+     * @param threshold - Threshold in threshold units (default: 100 tokens)
+     * @param bufferId - Signature buffer id for the possession signature (default: 0)
+     * @returns Signature of the seal_identity transaction
+     */
+    async reregisterIdentity(
+        pqcPublicKey: Uint8Array,
+        possessionSignature: Uint8Array,
+        threshold: bigint = DEFAULTS.threshold,
+        bufferId: bigint = BigInt(0)
+    ): Promise<string> {
+        const { pda } = this.getIdentityPda(this.wallet.publicKey);
+
+        // Anchor instruction discriminator for "reopen_identity"
+        const reopenDisc = Buffer.from([194, 10, 101, 156, 1, 87, 157, 45]);
+
+        const reopenArgs = Buffer.alloc(13);
+        reopenArgs.writeUInt32LE(pqcPublicKey.length, 0);
+        reopenArgs.writeUInt8(1, 4); // Some variant
+        reopenArgs.writeBigUInt64LE(threshold, 5);
+
+        await this.sendInstruction(new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: [
+                { pubkey: pda, isSigner: false, isWritable: true },
+                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
+                { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+            ],
+            data: Buffer.concat([reopenDisc, reopenArgs]),
+        }));
+
+        return this.uploadAndSealKey(pqcPublicKey, possessionSignature, bufferId);
+    }
+
+    /**
+     * write_key_chunk × n → seal_identity for an unsealed identity of the
+     * connected wallet.
      */
-    async updateThreshold(newThreshold: bigint): Promise<string> {
+    private async uploadAndSealKey(
+        pqcPublicKey: Uint8Array,
+        possessionSignature: Uint8Array,
+        bufferId: bigint
+    ): Promise<string> {
         const { pda } = this.getIdentityPda(this.wallet.publicKey);
 
+        // Anchor instruction discriminators
+        const writeDisc = Buffer.from([56, 136, 212, 3, 241, 24, 234, 151]);
+        const sealDisc = Buffer.from([15, 148, 228, 119, 91, 254, 84, 143]);
+
+        const identityKeys = [
+            { pubkey: pda, isSigner: false, isWritable: true },
+            { pubkey: this.wallet.publicKey, isSigner: true, isWritable: false },
+        ];
+
+        for (let offset = 0; offset < pqcPublicKey.length; offset += DEFAULTS.chunkSize) {
+            const chunk = pqcPublicKey.subarray(offset, offset + DEFAULTS.chunkSize);
+            const header = Buffer.alloc(8);
+            header.writeUInt32LE(offset, 0);
+            header.writeUInt32LE(chunk.length, 4);
+
+            await this.sendInstruction(new TransactionInstruction({
+                programId: this.quresisProgram,
+                keys: identityKeys,
+                data: Buffer.concat([writeDisc, header, Buffer.from(chunk)]),
+            }));
+        }
+
+        const staged = possessionSignature.length > DEFAULTS.chunkSize;
+        const signatureBuffer = staged
+            ? await this.uploadSignature(possessionSignature, bufferId)
+            : this.quresisProgram; // signature_buffer: None (program id placeholder)
+
+        const sigLenBuffer = Buffer.alloc(4);
+        sigLenBuffer.writeUInt32LE(staged ? 0 : possessionSignature.length, 0);
+
+        const signature = await this.sendInstruction(new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: [
+                ...identityKeys,
+                { pubkey: signatureBuffer, isSigner: false, isWritable: false },
+            ],
+            data: Buffer.concat([
+                sealDisc,
+                sigLenBuffer,
+                staged ? Buffer.alloc(0) : Buffer.from(possessionSignature),
+            ]),
+        }), staged ? DEFAULTS.sessionStepComputeUnits : undefined);
+
+        if (staged) {
+            await this.closeSignatureBuffer(bufferId);
+        }
+
+        return signature;
+    }
+
+    /**
+     * Update the threshold for quantum signature requirement
+     *
+     * Lowering the threshold only needs the wallet. Raising it also needs the
+     * key's signature over createThresholdMessage; signatures larger than one
+     * chunk are staged in signature buffer `bufferId`.
+     *
+     * @param newThreshold - New threshold in threshold units (THRESHOLD_DECIMALS)
+     * @param thresholdSignature - Key's signature over createThresholdMessage (only when raising)
+     * @param bufferId - Signature buffer id for a staged signature (default: 0)
+     * @returns Transaction signature
+     */
+    async updateThreshold(
+        newThreshold: bigint,
+        thresholdSignature: Uint8Array = new Uint8Array(0),
+        bufferId: bigint = BigInt(0)
+    ): Promise<string> {
         // Anchor instruction discriminator for "update_threshold"
-        const discriminator = Buffer.from([79, 112, 115, 87, 182, 145, 92, 78]);
+        const discriminator = Buffer.from([251, 36, 24, 179, 157, 31, 239, 234]);
 
         const thresholdBuffer = Buffer.alloc(8);
         thresholdBuffer.writeBigUInt64LE(newThreshold, 0);
 
-        const data = Buffer.concat([discriminator, thresholdBuffer]);
+        return this.sendSignedIdentityUpdate(
+            Buffer.concat([discriminator, thresholdBuffer]),
+            thresholdSignature,
+            bufferId
+        );
+    }
 
-        const ix = new TransactionInstruction({
+    /**
+     * Give `mint` its own threshold and 24h velocity limit, which the hook
+     * uses instead of the identity threshold for transfers of that mint.
+     *
+     * A policy stricter than the identity threshold only needs the wallet.
+     * Raising either value above it also needs the key's signature over
+     * createMintPolicyMessage; signatures larger than one chunk are staged in
+     * signature buffer `bufferId`.
+     *
+     * @param mint - Token-2022 mint the policy applies to
+     * @param thresholdAmount - Single transfer amount requiring PQC, in threshold units
+     * @param velocityLimit - 24h total requiring PQC, in threshold units
+     * @param policySignature - Key's signature over createMintPolicyMessage (only when loosening)
+     * @param bufferId - Signature buffer id for a staged signature (default: 0)
+     * @returns Transaction signature
+     */
+    async createMintPolicy(
+        mint: PublicKey,
+        thresholdAmount: bigint,
+        velocityLimit: bigint,
+        policySignature: Uint8Array = new Uint8Array(0),
+        bufferId: bigint = BigInt(0)
+    ): Promise<string> {
+        const { pda: identity } = this.getIdentityPda(this.wallet.publicKey);
+        const { pda } = this.getMintPolicyPda(this.wallet.publicKey, mint);
+
+        // Anchor instruction discriminator for "create_mint_policy"
+        const discriminator = Buffer.from([178, 204, 184, 33, 131, 67, 137, 184]);
+
+        return this.sendSignedIdentityUpdate(
+            Buffer.concat([discriminator, this.encodeMintPolicy(thresholdAmount, velocityLimit)]),
+            policySignature,
+            bufferId,
+            [
+                { pubkey: identity, isSigner: false, isWritable: true },
+                { pubkey: pda, isSigner: false, isWritable: true },
+                { pubkey: mint, isSigner: false, isWritable: false },
+                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
+                { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+            ]
+        );
+    }
+
+    /**
+     * Change the threshold and 24h velocity limit of a mint policy. Raising
+     * either value also needs the key's signature over
+     * createMintPolicyMessage, as in createMintPolicy.
+     *
+     * @param mint - Token-2022 mint of the policy
+     * @param thresholdAmount - New single transfer threshold, in threshold units
+     * @param velocityLimit - New 24h limit, in threshold units
+     * @param policySignature - Key's signature over createMintPolicyMessage (only when raising)
+     * @param bufferId - Signature buffer id for a staged signature (default: 0)
+     * @returns Transaction signature
+     */
+    async updateMintPolicy(
+        mint: PublicKey,
+        thresholdAmount: bigint,
+        velocityLimit: bigint,
+        policySignature: Uint8Array = new Uint8Array(0),
+        bufferId: bigint = BigInt(0)
+    ): Promise<string> {
+        const { pda: identity } = this.getIdentityPda(this.wallet.publicKey);
+        const { pda } = this.getMintPolicyPda(this.wallet.publicKey, mint);
+
+        // Anchor instruction discriminator for "update_mint_policy"
+        const discriminator = Buffer.from([119, 131, 232, 104, 38, 217, 16, 92]);
+
+        return this.sendSignedIdentityUpdate(
+            Buffer.concat([discriminator, this.encodeMintPolicy(thresholdAmount, velocityLimit)]),
+            policySignature,
+            bufferId,
+            [
+                { pubkey: identity, isSigner: false, isWritable: true },
+                { pubkey: pda, isSigner: false, isWritable: true },
+                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: false },
+            ]
+        );
+    }
+
+    /**
+     * Delete a mint policy and reclaim its rent; the mint falls back to the
+     * identity threshold. When that is higher than either policy value, the
+     * key's signature over createMintPolicyDeleteMessage is needed too.
+     *
+     * @param mint - Token-2022 mint of the policy
+     * @param deleteSignature - Key's signature over createMintPolicyDeleteMessage (only when loosening)
+     * @param bufferId - Signature buffer id for a staged signature (default: 0)
+     * @returns Transaction signature
+     */
+    async deleteMintPolicy(
+        mint: PublicKey,
+        deleteSignature: Uint8Array = new Uint8Array(0),
+        bufferId: bigint = BigInt(0)
+    ): Promise<string> {
+        const { pda: identity } = this.getIdentityPda(this.wallet.publicKey);
+        const { pda } = this.getMintPolicyPda(this.wallet.publicKey, mint);
+
+        // Anchor instruction discriminator for "delete_mint_policy"
+        const discriminator = Buffer.from([85, 196, 64, 184, 223, 213, 27, 171]);
+
+        return this.sendSignedIdentityUpdate(discriminator, deleteSignature, bufferId, [
+            { pubkey: identity, isSigner: false, isWritable: true },
+            { pubkey: pda, isSigner: false, isWritable: true },
+            { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
+        ]);
+    }
+
+    /**
+     * Get the mint policy PDA for a wallet's identity and a mint
+     */
+    getMintPolicyPda(authority: PublicKey, mint: PublicKey): { pda: PublicKey; bump: number } {
+        const { pda: identity } = this.getIdentityPda(authority);
+        return deriveMintPolicyPda(identity, mint, this.quresisProgram);
+    }
+
+    /**
+     * Fetch a wallet's threshold override for a mint, or `null` when the
+     * mint uses the identity threshold
+     */
+    async getMintPolicy(authority: PublicKey, mint: PublicKey): Promise<MintPolicy | null> {
+        const { pda } = this.getMintPolicyPda(authority, mint);
+        const account = await this.connection.getAccountInfo(pda);
+
+        if (!account || account.data.length === 0) {
+            return null;
+        }
+
+        // Fixed Borsh layout (see quresis-common); skip 8-byte discriminator
+        const data = account.data;
+        return {
+            identity: new PublicKey(data.subarray(8, 40)),
+            mint: new PublicKey(data.subarray(40, 72)),
+            thresholdAmount: data.readBigUInt64LE(72),
+            velocityLimit: data.readBigUInt64LE(80),
+            bump: data[88],
+        };
+    }
+
+    /** `threshold_amount (u64 LE) || velocity_limit (u64 LE)` instruction arguments */
+    private encodeMintPolicy(thresholdAmount: bigint, velocityLimit: bigint): Buffer {
+        const args = Buffer.alloc(16);
+        args.writeBigUInt64LE(thresholdAmount, 0);
+        args.writeBigUInt64LE(velocityLimit, 8);
+        return args;
+    }
+
+    /**
+     * Freeze the identity (emergency). The hook blocks every transfer until
+     * `unfreeze`.
+     *
+     * @returns Transaction signature
+     */
+    async freeze(): Promise<string> {
+        const { pda } = this.getIdentityPda(this.wallet.publicKey);
+
+        // Anchor instruction discriminator for "freeze"
+        const discriminator = Buffer.from([255, 91, 207, 84, 251, 194, 254, 63]);
+
+        return this.sendInstruction(new TransactionInstruction({
             programId: this.quresisProgram,
             keys: [
                 { pubkey: pda, isSigner: false, isWritable: true },
                 { pubkey: this.wallet.publicKey, isSigner: true, isWritable: false },
             ],
-            data,
-        });
+            data: discriminator,
+        }));
+    }
 
-        const tx = new Transaction().add(ix);
-        tx.feePayer = this.wallet.publicKey;
-        tx.recentBlockhash = (await this.connection.getLatestBlockhash()).blockhash;
+    /**
+     * Freeze any identity with its quantum key alone, for when the wallet's
+     * Ed25519 key is compromised. The connected wallet only relays and pays
+     * the fee; signatures larger than one chunk are staged in its signature
+     * buffer `bufferId`.
+     *
+     * @param authority - Owner of the identity to freeze
+     * @param freezeSignature - Identity key's signature over createFreezeMessage
+     * @param bufferId - Signature buffer id for a staged signature (default: 0)
+     * @returns Transaction signature
+     */
+    async freezeWithPqc(
+        authority: PublicKey,
+        freezeSignature: Uint8Array,
+        bufferId: bigint = BigInt(0)
+    ): Promise<string> {
+        const { pda } = this.getIdentityPda(authority);
 
-        const signed = await this.wallet.signTransaction(tx);
-        const signature = await this.connection.sendRawTransaction(signed.serialize());
+        // Anchor instruction discriminator for "freeze_with_pqc"
+        const discriminator = Buffer.from([90, 52, 122, 239, 81, 2, 115, 190]);
+
+        const staged = freezeSignature.length > DEFAULTS.chunkSize;
+        const signatureBuffer = staged
+            ? await this.uploadSignature(freezeSignature, bufferId)
+            : this.quresisProgram; // signature_buffer: None (program id placeholder)
+
+        const sigLenBuffer = Buffer.alloc(4);
+        sigLenBuffer.writeUInt32LE(staged ? 0 : freezeSignature.length, 0);
+
+        const signature = await this.sendInstruction(new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: [
+                { pubkey: pda, isSigner: false, isWritable: true },
+                { pubkey: signatureBuffer, isSigner: false, isWritable: false },
+            ],
+            data: Buffer.concat([
+                discriminator,
+                sigLenBuffer,
+                staged ? Buffer.alloc(0) : Buffer.from(freezeSignature),
+            ]),
+        }), staged ? DEFAULTS.sessionStepComputeUnits : undefined);
+
+        if (staged) {
+            await this.closeSignatureBuffer(bufferId);
+        }
 
-        await this.connection.confirmTransaction(signature);
         return signature;
     }
 
     /**
-     * Toggle freeze state of the identity
-     * 
+     * Lift a freeze. Needs the key's signature over createUnfreezeMessage;
+     * signatures larger than one chunk are staged in signature buffer `bufferId`.
+     *
+     * @param unfreezeSignature - Key's signature over createUnfreezeMessage
+     * @param bufferId - Signature buffer id for a staged signature (default: 0)
      * @returns Transaction signature
      */
-    async toggleFreeze(): Promise<string> {
+    async unfreeze(
+        unfreezeSignature: Uint8Array,
+        bufferId: bigint = BigInt(0)
+    ): Promise<string> {
+        // Anchor instruction discriminator for "unfreeze"
+        const discriminator = Buffer.from([133, 160, 68, 253, 80, 232, 218, 247]);
+
+        return this.sendSignedIdentityUpdate(discriminator, unfreezeSignature, bufferId);
+    }
+
+    /**
+     * Send an instruction whose accounts are `keys` (by default identity,
+     * authority) followed by signature_buffer and whose last argument is a
+     * PQC signature, staging the signature in a buffer when it does not fit
+     * one chunk.
+     */
+    private async sendSignedIdentityUpdate(
+        data: Buffer,
+        pqcSignature: Uint8Array,
+        bufferId: bigint,
+        keys: AccountMeta[] = [
+            { pubkey: this.getIdentityPda(this.wallet.publicKey).pda, isSigner: false, isWritable: true },
+            { pubkey: this.wallet.publicKey, isSigner: true, isWritable: false },
+        ]
+    ): Promise<string> {
+
+        const staged = pqcSignature.length > DEFAULTS.chunkSize;
+        const signatureBuffer = staged
+            ? await this.uploadSignature(pqcSignature, bufferId)
+            : this.quresisProgram; // signature_buffer: None (program id placeholder)
+
+        const sigLenBuffer = Buffer.alloc(4);
+        sigLenBuffer.writeUInt32LE(staged ? 0 : pqcSignature.length, 0);
+
+        const signature = await this.sendInstruction(new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: [
+                ...keys,
+                { pubkey: signatureBuffer, isSigner: false, isWritable: false },
+            ],
+            data: Buffer.concat([
+                data,
+                sigLenBuffer,
+                staged ? Buffer.alloc(0) : Buffer.from(pqcSignature),
+            ]),
+        }), staged ? DEFAULTS.sessionStepComputeUnits : undefined);
+
+        if (staged) {
+            await this.closeSignatureBuffer(bufferId);
+        }
+
+        return signature;
+    }
+
+    /**
+     * Revoke the connected wallet's identity.
+     *
+     * A sealed identity needs its key's signature over createCloseMessage;
+     * signatures larger than one chunk are staged in signature buffer
+     * `bufferId`. The account becomes a tombstone that keeps the hook
+     * blocking transfers until reregisterIdentity. An unsealed registration
+     * is simply closed (pass an empty signature).
+     *
+     * @param closeSignature - Current key's signature over createCloseMessage
+     * @param bufferId - Signature buffer id for a staged signature (default: 0)
+     * @returns Transaction signature
+     */
+    async closeIdentity(
+        closeSignature: Uint8Array,
+        bufferId: bigint = BigInt(0)
+    ): Promise<string> {
         const { pda } = this.getIdentityPda(this.wallet.publicKey);
 
-        // Anchor instruction discriminator for "toggle_freeze"
-        const discriminator = Buffer.from([126, 245, 172, 144, 148, 158, 144, 54]);
+        // Anchor instruction discriminator for "close_identity"
+        const discriminator = Buffer.from([158, 183, 97, 254, 215, 179, 139, 12]);
+
+        const staged = closeSignature.length > DEFAULTS.chunkSize;
+        const signatureBuffer = staged
+            ? await this.uploadSignature(closeSignature, bufferId)
+            : this.quresisProgram; // signature_buffer: None (program id placeholder)
+
+        const sigLenBuffer = Buffer.alloc(4);
+        sigLenBuffer.writeUInt32LE(staged ? 0 : closeSignature.length, 0);
+
+        const signature = await this.sendInstruction(new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: [
+                { pubkey: pda, isSigner: false, isWritable: true },
+                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
+                { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+                { pubkey: signatureBuffer, isSigner: false, isWritable: false },
+            ],
+            data: Buffer.concat([
+                discriminator,
+                sigLenBuffer,
+                staged ? Buffer.alloc(0) : Buffer.from(closeSignature),
+            ]),
+        }), staged ? DEFAULTS.sessionStepComputeUnits : undefined);
+
+        if (staged) {
+            await this.closeSignatureBuffer(bufferId);
+        }
+
+        return signature;
+    }
+
+    /**
+     * Move an identity to the connected wallet, for when the old wallet's
+     * Ed25519 key is compromised. The old wallet does not sign: the identity
+     * key's signature over createMigrateAuthorityMessage (with the connected
+     * wallet as the new authority) authorizes the move, and the connected
+     * wallet pays for its new identity. Signatures larger than one chunk are
+     * staged in signature buffer `bufferId`. Every mint policy of the old
+     * identity is found and moved along with it.
+     *
+     * @param oldAuthority - Wallet the identity currently belongs to
+     * @param migrateSignature - Identity key's signature over createMigrateAuthorityMessage
+     * @param bufferId - Signature buffer id for a staged signature (default: 0)
+     * @returns Transaction signature
+     */
+    async migrateAuthority(
+        oldAuthority: PublicKey,
+        migrateSignature: Uint8Array,
+        bufferId: bigint = BigInt(0)
+    ): Promise<string> {
+        const { pda: oldPda } = this.getIdentityPda(oldAuthority);
+        const { pda: newPda } = this.getIdentityPda(this.wallet.publicKey);
+
+        // Anchor instruction discriminator for "migrate_authority"
+        const discriminator = Buffer.from([208, 84, 72, 24, 205, 144, 51, 86]);
+
+        const staged = migrateSignature.length > DEFAULTS.chunkSize;
+        const signatureBuffer = staged
+            ? await this.uploadSignature(migrateSignature, bufferId)
+            : this.quresisProgram; // signature_buffer: None (program id placeholder)
+
+        const sigLenBuffer = Buffer.alloc(4);
+        sigLenBuffer.writeUInt32LE(staged ? 0 : migrateSignature.length, 0);
+
+        // Remaining accounts: each MintPolicy of the old identity, followed by
+        // its PDA under the new one (fixed layout, see quresis-common)
+        const policies = await this.connection.getProgramAccounts(this.quresisProgram, {
+            filters: [
+                { dataSize: 89 },
+                // Anchor account discriminator for "MintPolicy"
+                { memcmp: { offset: 0, bytes: bs58.encode(Buffer.from([191, 78, 230, 47, 121, 49, 66, 222])) } },
+                { memcmp: { offset: 8, bytes: oldPda.toBase58() } },
+            ],
+        });
+        const policyKeys = policies.flatMap(({ pubkey, account }) => {
+            const mint = new PublicKey(account.data.subarray(40, 72));
+            return [
+                { pubkey, isSigner: false, isWritable: true },
+                {
+                    pubkey: deriveMintPolicyPda(newPda, mint, this.quresisProgram).pda,
+                    isSigner: false,
+                    isWritable: true,
+                },
+            ];
+        });
+
+        const signature = await this.sendInstruction(new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: [
+                { pubkey: oldPda, isSigner: false, isWritable: true },
+                { pubkey: newPda, isSigner: false, isWritable: true },
+                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
+                { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+                { pubkey: signatureBuffer, isSigner: false, isWritable: false },
+                ...policyKeys,
+            ],
+            data: Buffer.concat([
+                discriminator,
+                sigLenBuffer,
+                staged ? Buffer.alloc(0) : Buffer.from(migrateSignature),
+            ]),
+        }), staged ? DEFAULTS.sessionStepComputeUnits : undefined);
+
+        if (staged) {
+            await this.closeSignatureBuffer(bufferId);
+        }
+
+        return signature;
+    }
+
+    /**
+     * Upgrade an identity stored in an older account layout to the current one.
+     * Migration keeps every field, so the connected wallet can pay for any
+     * authority's identity.
+     *
+     * @param authority - Owner of the identity to migrate (default: connected wallet)
+     * @returns Transaction signature
+     */
+    async migrateIdentity(authority: PublicKey = this.wallet.publicKey): Promise<string> {
+        const { pda } = this.getIdentityPda(authority);
+
+        // Anchor instruction discriminator for "migrate_identity"
+        const discriminator = Buffer.from([161, 192, 70, 80, 47, 37, 26, 10]);
 
         const ix = new TransactionInstruction({
             programId: this.quresisProgram,
             keys: [
                 { pubkey: pda, isSigner: false, isWritable: true },
-                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: false },
+                { pubkey: authority, isSigner: false, isWritable: false },
+                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
+                { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
             ],
             data: discriminator,
         });
@@ -235,6 +824,442 @@ export class QuresisClient {
         return signature;
     }
 
+    // ==========================================================================
+    // Signature Buffers
+    // ==========================================================================
+
+    /**
+     * Get the signature buffer PDA for the connected wallet
+     */
+    getSignatureBufferPda(bufferId: bigint): { pda: PublicKey; bump: number } {
+        return deriveSignatureBufferPda(this.wallet.publicKey, bufferId, this.quresisProgram);
+    }
+
+    /**
+     * Upload an ML-DSA signature into a SignatureBuffer and finalize it.
+     *
+     * Real signatures (2420 / 3309 bytes) exceed the 1232-byte transaction MTU,
+     * so they are staged with init_buffer → write_chunk × n → finalize. Pass
+     * the returned PDA as `signatureBuffer` to verify_signature / rotate_key.
+     * A new public key for rotate_key (`newKeyBuffer`) is staged the same way.
+     *
+     * @param signature - Raw ML-DSA signature (or public key) bytes
+     * @param bufferId - Caller-chosen buffer id (default: 0)
+     * @returns The finalized buffer PDA
+     */
+    async uploadSignature(signature: Uint8Array, bufferId: bigint = BigInt(0)): Promise<PublicKey> {
+        const { pda } = this.getSignatureBufferPda(bufferId);
+
+        // Anchor instruction discriminators
+        const initDisc = Buffer.from([123, 211, 233, 210, 166, 139, 218, 60]);
+        const writeDisc = Buffer.from([93, 141, 167, 15, 209, 133, 137, 51]);
+        const finalizeDisc = Buffer.from([171, 61, 218, 56, 127, 115, 12, 217]);
+
+        const initArgs = Buffer.alloc(12);
+        initArgs.writeBigUInt64LE(bufferId, 0);
+        initArgs.writeUInt32LE(signature.length, 8);
+
+        await this.sendInstruction(new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: [
+                { pubkey: pda, isSigner: false, isWritable: true },
+                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
+                { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+            ],
+            data: Buffer.concat([initDisc, initArgs]),
+        }));
+
+        const bufferKeys = [
+            { pubkey: pda, isSigner: false, isWritable: true },
+            { pubkey: this.wallet.publicKey, isSigner: true, isWritable: false },
+        ];
+
+        for (let offset = 0; offset < signature.length; offset += DEFAULTS.chunkSize) {
+            const chunk = signature.subarray(offset, offset + DEFAULTS.chunkSize);
+            const header = Buffer.alloc(8);
+            header.writeUInt32LE(offset, 0);
+            header.writeUInt32LE(chunk.length, 4);
+
+            await this.sendInstruction(new TransactionInstruction({
+                programId: this.quresisProgram,
+                keys: bufferKeys,
+                data: Buffer.concat([writeDisc, header, Buffer.from(chunk)]),
+            }));
+        }
+
+        await this.sendInstruction(new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: bufferKeys,
+            data: finalizeDisc,
+        }));
+
+        return pda;
+    }
+
+    /**
+     * Close a signature buffer and reclaim its rent
+     *
+     * @param bufferId - Buffer id used in uploadSignature
+     * @returns Transaction signature
+     */
+    async closeSignatureBuffer(bufferId: bigint = BigInt(0)): Promise<string> {
+        const { pda } = this.getSignatureBufferPda(bufferId);
+
+        // Anchor instruction discriminator for "close_buffer"
+        const discriminator = Buffer.from([46, 114, 179, 58, 57, 45, 194, 172]);
+
+        return this.sendInstruction(new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: [
+                { pubkey: pda, isSigner: false, isWritable: true },
+                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
+            ],
+            data: discriminator,
+        }));
+    }
+
+    // ==========================================================================
+    // Verification Sessions
+    // ==========================================================================
+
+    /**
+     * Get the verification session PDA for the connected wallet
+     */
+    getVerificationSessionPda(sessionId: bigint): { pda: PublicKey; bump: number } {
+        return deriveVerificationSessionPda(this.wallet.publicKey, sessionId, this.quresisProgram);
+    }
+
+    /**
+     * Verify an ML-DSA signature across several transactions.
+     *
+     * Sends begin_verification, then advance_verification once for NTT(z)
+     * and once per row of A (4 for ML-DSA-44, 6 for ML-DSA-65). The
+     * finished session can be passed as `verificationSession` to
+     * verify_signature, which consumes it.
+     *
+     * @param identityAuthority - Owner of the identity whose key signed
+     * @param message - The signed message
+     * @param signatureBuffer - Finalized buffer from uploadSignature
+     * @param sessionId - Caller-chosen session id (default: 0)
+     * @returns The session PDA
+     */
+    async runVerificationSession(
+        identityAuthority: PublicKey,
+        message: Uint8Array,
+        signatureBuffer: PublicKey,
+        sessionId: bigint = BigInt(0)
+    ): Promise<PublicKey> {
+        const identity = await this.getIdentity(identityAuthority);
+        if (!identity) {
+            throw new Error('No Quantum Identity registered for this authority');
+        }
+        const { pda: identityPda } = this.getIdentityPda(identityAuthority);
+        const { pda } = this.getVerificationSessionPda(sessionId);
+
+        // Anchor instruction discriminators
+        const beginDisc = Buffer.from([6, 173, 175, 164, 204, 186, 106, 218]);
+        const advanceDisc = Buffer.from([236, 219, 113, 114, 26, 166, 59, 175]);
+
+        const idBuffer = Buffer.alloc(8);
+        idBuffer.writeBigUInt64LE(sessionId, 0);
+        const messageLen = Buffer.alloc(4);
+        messageLen.writeUInt32LE(message.length, 0);
+        const emptySignature = Buffer.alloc(4); // signature comes from the buffer
+
+        await this.sendInstruction(new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: [
+                { pubkey: pda, isSigner: false, isWritable: true },
+                { pubkey: identityPda, isSigner: false, isWritable: false },
+                { pubkey: signatureBuffer, isSigner: false, isWritable: false },
+                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
+                { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+            ],
+            data: Buffer.concat([beginDisc, idBuffer, messageLen, Buffer.from(message), emptySignature]),
+        }), DEFAULTS.sessionStepComputeUnits);
+
+        const rows = identity.pqcPublicKey.length === 1952 ? 6 : 4;
+        for (let step = 0; step <= rows; step++) {
+            await this.sendInstruction(new TransactionInstruction({
+                programId: this.quresisProgram,
+                keys: [
+                    { pubkey: pda, isSigner: false, isWritable: true },
+                    { pubkey: identityPda, isSigner: false, isWritable: false },
+                ],
+                data: advanceDisc,
+            }), DEFAULTS.sessionStepComputeUnits);
+        }
+
+        return pda;
+    }
+
+    /**
+     * Close a verification session and reclaim its rent
+     *
+     * @param sessionId - Session id used in runVerificationSession
+     * @returns Transaction signature
+     */
+    async closeVerificationSession(sessionId: bigint = BigInt(0)): Promise<string> {
+        const { pda } = this.getVerificationSessionPda(sessionId);
+
+        // Anchor instruction discriminator for "close_verification"
+        const discriminator = Buffer.from([61, 217, 221, 113, 140, 80, 8, 93]);
+
+        return this.sendInstruction(new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: [
+                { pubkey: pda, isSigner: false, isWritable: true },
+                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
+            ],
+            data: discriminator,
+        }));
+    }
+
+    // ==========================================================================
+    // Transfer Authorizations
+    // ==========================================================================
+
+    /**
+     * Get the transfer authorization PDA for a wallet's identity and a mint
+     */
+    getTransferAuthorizationPda(authority: PublicKey, mint: PublicKey): { pda: PublicKey; bump: number } {
+        const { pda: identity } = this.getIdentityPda(authority);
+        return deriveTransferAuthorizationPda(identity, mint, this.quresisProgram);
+    }
+
+    /**
+     * Build a verify_signature instruction for the connected wallet's identity
+     *
+     * Placed right before a Token-2022 transfer, with `message` from
+     * `QuresisSigner.createTransferMessage` and no `authorization`, it lets
+     * the hook authorize a HardEnforce transfer in the same transaction.
+     * The wallet signs as the identity authority, which the program requires
+     * for such a message. With `authorization`, it mints a
+     * TransferAuthorization receipt instead.
+     *
+     * @param message - The signed message (ends with the current sequence)
+     * @param signatureBuffer - Finalized buffer holding the signature (from uploadSignature)
+     * @param verificationSession - Verified session to use instead of the buffer
+     * @param authorization - Receipt parameters (must match `message`)
+     * @returns Transaction instruction
+     */
+    buildVerifySignatureInstruction(
+        message: Uint8Array,
+        signatureBuffer: PublicKey | null,
+        verificationSession?: PublicKey,
+        authorization?: TransferAuthorizationParams
+    ): TransactionInstruction {
+        const { pda: identityPda } = this.getIdentityPda(this.wallet.publicKey);
+
+        // Anchor instruction discriminator for "verify_signature"
+        const discriminator = Buffer.from([91, 139, 24, 69, 251, 162, 245, 112]);
+
+        const messageLen = Buffer.alloc(4);
+        messageLen.writeUInt32LE(message.length, 0);
+        const emptySignature = Buffer.alloc(4); // signature comes from the buffer or session
+
+        let authorizationArg = Buffer.from([0]); // None variant
+        let receipt = this.quresisProgram; // Absent optional accounts are passed as the program id
+        if (authorization) {
+            authorizationArg = Buffer.alloc(1 + 32 + 32 + 8 + 8);
+            authorizationArg.writeUInt8(1, 0); // Some variant
+            authorization.mint.toBuffer().copy(authorizationArg, 1);
+            authorization.destination.toBuffer().copy(authorizationArg, 33);
+            authorizationArg.writeBigUInt64LE(authorization.amountCap, 65);
+            authorizationArg.writeBigUInt64LE(authorization.expiresAtSlot, 73);
+            receipt = this.getTransferAuthorizationPda(this.wallet.publicKey, authorization.mint).pda;
+        }
+
+        return new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: [
+                { pubkey: identityPda, isSigner: false, isWritable: true },
+                { pubkey: signatureBuffer ?? this.quresisProgram, isSigner: false, isWritable: false },
+                {
+                    pubkey: verificationSession ?? this.quresisProgram,
+                    isSigner: false,
+                    isWritable: verificationSession !== undefined,
+                },
+                { pubkey: receipt, isSigner: false, isWritable: authorization !== undefined },
+                {
+                    pubkey: authorization ? this.wallet.publicKey : this.quresisProgram,
+                    isSigner: authorization !== undefined,
+                    isWritable: authorization !== undefined,
+                },
+                {
+                    pubkey: authorization ? SystemProgram.programId : this.quresisProgram,
+                    isSigner: false,
+                    isWritable: false,
+                },
+                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: false },
+            ],
+            data: Buffer.concat([
+                discriminator,
+                messageLen,
+                Buffer.from(message),
+                emptySignature,
+                authorizationArg,
+            ]),
+        });
+    }
+
+    /**
+     * Authorize one high-value HardEnforce transfer of the connected wallet.
+     *
+     * Sends verify_signature with `params`, minting a TransferAuthorization
+     * that the transfer hook consumes. `message` must be
+     * `QuresisSigner.createTransferMessage` for the same parameters and the
+     * identity's current sequence.
+     *
+     * @param params - Mint, destination token account, amount cap and expiry slot
+     * @param message - The signed transfer message
+     * @param signatureBuffer - Finalized buffer holding the signature (from uploadSignature)
+     * @param verificationSession - Verified session to use instead of the buffer
+     * @returns Transaction signature
+     */
+    async authorizeTransfer(
+        params: TransferAuthorizationParams,
+        message: Uint8Array,
+        signatureBuffer: PublicKey | null,
+        verificationSession?: PublicKey
+    ): Promise<string> {
+        const ix = this.buildVerifySignatureInstruction(
+            message,
+            signatureBuffer,
+            verificationSession,
+            params
+        );
+        return this.sendInstruction(ix, DEFAULTS.sessionStepComputeUnits);
+    }
+
+    /**
+     * Close an unused transfer authorization of the connected wallet; the
+     * rent goes back to whoever paid for it
+     *
+     * @param mint - Mint the authorization was issued for
+     * @returns Transaction signature
+     */
+    async revokeTransferAuthorization(mint: PublicKey): Promise<string> {
+        const { pda: identityPda } = this.getIdentityPda(this.wallet.publicKey);
+        const { pda } = this.getTransferAuthorizationPda(this.wallet.publicKey, mint);
+
+        // Anchor instruction discriminator for "revoke_transfer_authorization"
+        const discriminator = Buffer.from([114, 77, 86, 104, 221, 15, 104, 15]);
+
+        return this.sendInstruction(new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: [
+                { pubkey: identityPda, isSigner: false, isWritable: false },
+                { pubkey: pda, isSigner: false, isWritable: true },
+                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: false },
+                { pubkey: await this.getTransferAuthorizationPayer(pda), isSigner: false, isWritable: true },
+            ],
+            data: discriminator,
+        }));
+    }
+
+    /**
+     * Close a consumed or expired transfer authorization of any wallet and
+     * return its rent to whoever paid for it. A new authorization for the
+     * same mint can only be minted once the old receipt is closed.
+     *
+     * @param authority - Wallet whose identity the authorization belongs to
+     * @param mint - Mint the authorization was issued for
+     * @returns Transaction signature
+     */
+    async closeTransferAuthorization(authority: PublicKey, mint: PublicKey): Promise<string> {
+        const { pda } = this.getTransferAuthorizationPda(authority, mint);
+
+        // Anchor instruction discriminator for "close_transfer_authorization"
+        const discriminator = Buffer.from([166, 114, 90, 99, 73, 213, 237, 121]);
+
+        return this.sendInstruction(new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: [
+                { pubkey: pda, isSigner: false, isWritable: true },
+                { pubkey: await this.getTransferAuthorizationPayer(pda), isSigner: false, isWritable: true },
+            ],
+            data: discriminator,
+        }));
+    }
+
+    /** Account that paid for a TransferAuthorization receipt */
+    private async getTransferAuthorizationPayer(pda: PublicKey): Promise<PublicKey> {
+        const account = await this.connection.getAccountInfo(pda);
+        if (!account) {
+            throw new Error('No transfer authorization for this mint');
+        }
+        // discriminator (8) + identity, mint, destination (3 x 32) + cap, expiry, sequence (3 x 8)
+        return new PublicKey(account.data.subarray(128, 160));
+    }
+
+    // ==========================================================================
+    // Velocity Windows
+    // ==========================================================================
+
+    /**
+     * Get the velocity state PDA for a wallet's identity and a mint
+     */
+    getVelocityStatePda(authority: PublicKey, mint: PublicKey): { pda: PublicKey; bump: number } {
+        const { pda: identity } = this.getIdentityPda(authority);
+        return deriveVelocityStatePda(identity, mint, this.quresisProgram);
+    }
+
+    /**
+     * Create the connected wallet's velocity window on a mint, paying its rent
+     *
+     * Otherwise the hook creates it on the first transfer of the mint with
+     * the issuer's HookConfig funds, which may run out. Does nothing if the
+     * window already exists.
+     *
+     * @param mint - Mint whose transfers the window counts
+     * @returns Transaction signature
+     */
+    async initVelocityState(mint: PublicKey): Promise<string> {
+        const { pda: identityPda } = this.getIdentityPda(this.wallet.publicKey);
+        const { pda } = this.getVelocityStatePda(this.wallet.publicKey, mint);
+
+        // Anchor instruction discriminator for "init_velocity_state"
+        const discriminator = Buffer.from([160, 190, 65, 15, 223, 34, 67, 212]);
+
+        return this.sendInstruction(new TransactionInstruction({
+            programId: this.quresisProgram,
+            keys: [
+                { pubkey: identityPda, isSigner: false, isWritable: false },
+                { pubkey: pda, isSigner: false, isWritable: true },
+                { pubkey: mint, isSigner: false, isWritable: false },
+                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
+                { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+            ],
+            data: discriminator,
+        }));
+    }
+
+    /**
+     * Fetch a wallet's 24h velocity window on a mint
+     *
+     * `null` until `initVelocityState` or the wallet's first recorded
+     * transfer of the mint creates it.
+     */
+    async getVelocityState(authority: PublicKey, mint: PublicKey): Promise<VelocityState | null> {
+        const { pda } = this.getVelocityStatePda(authority, mint);
+        const account = await this.connection.getAccountInfo(pda);
+
+        if (!account || account.data.length === 0) {
+            return null;
+        }
+
+        // Fixed Borsh layout (see quresis-common); skip 8-byte discriminator
+        const data = account.data;
+        return {
+            identity: new PublicKey(data.subarray(8, 40)),
+            mint: new PublicKey(data.subarray(40, 72)),
+            windowStart: data.readBigInt64LE(72),
+            windowAmount: data.readBigUInt64LE(80),
+            bump: data[88],
+        };
+    }
+
     // ==========================================================================
     // Hook Management
     // ==========================================================================
@@ -260,20 +1285,40 @@ export class QuresisClient {
         return this.parseHookConfig(account.data);
     }
 
+    // ==========================================================================
+    // Transaction Helpers
+    // ==========================================================================
+
+    private async sendInstruction(
+        ix: TransactionInstruction,
+        computeUnits?: number
+    ): Promise<string> {
+        const tx = new Transaction();
+        if (computeUnits !== undefined) {
+            tx.add(ComputeBudgetProgram.setComputeUnitLimit({ units: computeUnits }));
+        }
+        tx.add(ix);
+        tx.feePayer = this.wallet.publicKey;
+        tx.recentBlockhash = (await this.connection.getLatestBlockhash()).blockhash;
+
+        const signed = await this.wallet.signTransaction(tx);
+        const signature = await this.connection.sendRawTransaction(signed.serialize());
+
+        await this.connection.confirmTransaction(signature);
+        return signature;
+    }
+
     // ==========================================================================
     // Parsers
     // ==========================================================================
 
     private parseQuantumIdentity(data: Buffer): QuantumIdentity {
-        // Skip 8-byte discriminator
+        // Zero-copy header (see quresis-common); skip 8-byte discriminator
         let offset = 8;
 
         const authority = new PublicKey(data.subarray(offset, offset + 32));
         offset += 32;
 
-        const bump = data[offset];
-        offset += 1;
-
         const sequence = data.readBigUInt64LE(offset);
         offset += 8;
 
@@ -283,19 +1328,47 @@ export class QuresisClient {
         const createdAt = data.readBigInt64LE(offset);
         offset += 8;
 
-        const isFrozen = data[offset] === 1;
-        offset += 1;
-
         const thresholdAmount = data.readBigUInt64LE(offset);
         offset += 8;
 
+        // current_window_start, current_window_amount (unused, see getVelocityState)
+        offset += 16;
+
         const keyVersion = data.readUInt16LE(offset);
         offset += 2;
 
-        const keyLen = data.readUInt32LE(offset);
+        // key_written_len
+        offset += 2;
+
+        const keyLen = data.readUInt16LE(offset);
+        offset += 2;
+
+        const bump = data[offset];
+        offset += 1;
+
+        const isFrozen = data[offset] === 1;
+        offset += 1;
+
+        const isSealed = data[offset] === 1;
+        offset += 1;
+
+        const version = data[offset];
+        offset += 1;
+
+        const isRevoked = data[offset] === 1;
+        offset += 1;
+
+        const isForwarded = data[offset] === 1;
+        offset += 1;
+
+        // mint_policy_count, reserved; the public key (or a forwarding address)
+        // follows the 104-byte header
         offset += 4;
 
         const pqcPublicKey = new Uint8Array(data.subarray(offset, offset + keyLen));
+        const forwardedTo = isForwarded
+            ? new PublicKey(data.subarray(offset, offset + 32))
+            : null;
 
         return {
             authority,
@@ -306,6 +1379,10 @@ export class QuresisClient {
             isFrozen,
             thresholdAmount,
             keyVersion,
+            isSealed,
+            version,
+            isRevoked,
+            forwardedTo,
             pqcPublicKey,
         };
     }
@@ -330,6 +1407,15 @@ export class QuresisClient {
         offset += 8;
 
         const bump = data[offset];
+        offset += 1;
+
+        // extra_meta_bump
+        offset += 1;
+
+        // Configs not yet run through migrate_hook_config end here; the
+        // migration gives them AllowUnregistered
+        const failurePolicy =
+            offset < data.length ? (data[offset] as FailurePolicy) : FailurePolicy.AllowUnregistered;
 
         return {
             mint,
@@ -338,6 +1424,7 @@ export class QuresisClient {
             totalTransfersChecked,
             highValueTransfersDetected,
             bump,
+            failurePolicy,
         };
     }
 }
@@ -31,7 +31,7 @@ export const ML_DSA_CONSTANTS = {
     'ML-DSA-65': {
         publicKeySize: 1952,
         secretKeySize: 4032,
-        signatureSize: 3293,
+        signatureSize: 3309,
         securityLevel: 3,
     },
 } as const;
@@ -44,14 +44,35 @@ export const SEED_PREFIXES = {
     identity: Buffer.from('quresis_id'),
     /** Hook Config PDA seed */
     hookConfig: Buffer.from('quresis_hook'),
+    /** Signature Buffer PDA seed */
+    signatureBuffer: Buffer.from('quresis_sig'),
+    /** Verification Session PDA seed */
+    verificationSession: Buffer.from('quresis_verify'),
+    /** Transfer Authorization PDA seed */
+    transferAuthorization: Buffer.from('quresis_auth'),
+    /** Velocity State PDA seed (one per identity and mint) */
+    velocityState: Buffer.from('quresis_velocity'),
+    /** Mint Policy PDA seed (one per identity and mint) */
+    mintPolicy: Buffer.from('quresis_mint_policy'),
 } as const;
 
+/**
+ * Decimals of identity thresholds and the velocity window: 10^9 units are one
+ * whole token of any mint (lamports for SOL). The hook converts each transfer
+ * from its mint's decimals before comparing.
+ */
+export const THRESHOLD_DECIMALS = 9;
+
 /**
  * Default configuration values
  */
 export const DEFAULTS = {
-    /** Default threshold in lamports (100 SOL) */
+    /** Default threshold in threshold units (100 tokens) */
     threshold: BigInt(100_000_000_000),
     /** Default ML-DSA variant */
     variant: 'ML-DSA-44' as const,
+    /** Bytes per write_chunk transaction (stays well under the 1232-byte MTU) */
+    chunkSize: 900,
+    /** Compute unit limit requested for each verification session step */
+    sessionStepComputeUnits: 1_400_000,
 } as const;
@@ -34,20 +34,30 @@ export type {
     MLDSAVariant,
     QuantumIdentity,
     HookConfig,
+    VelocityState,
+    MintPolicy,
+    TransferAuthorizationParams,
 } from './types';
-export { EnforcementMode } from './types';
+export { EnforcementMode, FailurePolicy } from './types';
 
 // Constants
 export {
     PROGRAM_IDS,
     ML_DSA_CONSTANTS,
     SEED_PREFIXES,
+    THRESHOLD_DECIMALS,
 } from './constants';
 
 // Utilities
 export {
     deriveIdentityPda,
     deriveHookConfigPda,
+    deriveSignatureBufferPda,
+    deriveVerificationSessionPda,
+    deriveTransferAuthorizationPda,
+    deriveVelocityStatePda,
+    deriveMintPolicyPda,
+    toThresholdUnits,
     bufferToHex,
     hexToBuffer,
 } from './utils';
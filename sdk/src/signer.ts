@@ -105,8 +105,54 @@ export class QuresisSigner {
         };
     }
 
+    /**
+     * Append the anti-replay sequence to an arbitrary payload.
+     *
+     * verify_signature only accepts messages that end with the identity's
+     * current sequence (u64 LE), and advances the sequence on success.
+     *
+     * @param payload - Application-specific message bytes
+     * @param sequence - Current sequence number from identity
+     * @returns Message bytes to sign
+     */
+    createSequencedMessage(payload: Uint8Array, sequence: bigint): Uint8Array {
+        const message = new Uint8Array(payload.length + 8);
+        message.set(payload, 0);
+        new DataView(message.buffer).setBigUint64(payload.length, sequence, true);
+        return message;
+    }
+
+    /**
+     * Create the proof-of-possession message for a new key
+     *
+     * register_identity, seal_identity and rotate_key require the new key's
+     * signature over this message, binding the key to one wallet and program.
+     *
+     * @param authority - Wallet public key bytes
+     * @param programId - Quresis program id bytes
+     * @returns Message bytes to sign with the new key
+     */
+    createPossessionMessage(
+        authority: Uint8Array,
+        programId: Uint8Array
+    ): Uint8Array {
+        const prefix = new TextEncoder().encode('QURESIS_KEY_POSSESSION_V1:');
+
+        const message = new Uint8Array(
+            prefix.length + programId.length + authority.length
+        );
+        message.set(prefix, 0);
+        message.set(programId, prefix.length);
+        message.set(authority, prefix.length + programId.length);
+
+        return message;
+    }
+
     /**
      * Create a message for key rotation authorization
+     *
+     * rotate_key rebuilds exactly this message on-chain and checks the
+     * current key's signature over it.
      * 
      * @param newPublicKey - The new public key being rotated to
      * @param sequence - Current sequence number from identity
@@ -130,32 +176,205 @@ export class QuresisSigner {
         return message;
     }
 
+    /**
+     * Create the message that revokes an identity
+     *
+     * close_identity rebuilds exactly this message on-chain and checks the
+     * current key's signature over it.
+     *
+     * @param authority - Wallet public key bytes
+     * @param sequence - Current sequence number from identity
+     * @returns Message bytes to sign
+     */
+    createCloseMessage(
+        authority: Uint8Array,
+        sequence: bigint
+    ): Uint8Array {
+        return this.createIdentityActionMessage('QURESIS_CLOSE_IDENTITY_V1:', authority, new Uint8Array(0), sequence);
+    }
+
+    /**
+     * Create the message that moves an identity to a new wallet
+     *
+     * migrate_authority rebuilds exactly this message on-chain; the old
+     * wallet does not sign, the new one does.
+     *
+     * @param authority - Current (old) wallet public key bytes
+     * @param newAuthority - New wallet public key bytes
+     * @param sequence - Current sequence number from identity
+     * @returns Message bytes to sign
+     */
+    createMigrateAuthorityMessage(
+        authority: Uint8Array,
+        newAuthority: Uint8Array,
+        sequence: bigint
+    ): Uint8Array {
+        return this.createIdentityActionMessage('QURESIS_MIGRATE_AUTHORITY_V1:', authority, newAuthority, sequence);
+    }
+
+    /**
+     * Create the message that freezes an identity without its wallet
+     *
+     * freeze_with_pqc rebuilds exactly this message on-chain; anyone can
+     * relay the signed message, so the compromised Ed25519 key is not needed.
+     *
+     * @param authority - Wallet public key bytes
+     * @param sequence - Current sequence number from identity
+     * @returns Message bytes to sign
+     */
+    createFreezeMessage(
+        authority: Uint8Array,
+        sequence: bigint
+    ): Uint8Array {
+        return this.createIdentityActionMessage('QURESIS_FREEZE_V1:', authority, new Uint8Array(0), sequence);
+    }
+
+    /**
+     * Create the message that lifts a freeze
+     *
+     * unfreeze rebuilds exactly this message on-chain and checks the
+     * current key's signature over it.
+     *
+     * @param authority - Wallet public key bytes
+     * @param sequence - Current sequence number from identity
+     * @returns Message bytes to sign
+     */
+    createUnfreezeMessage(
+        authority: Uint8Array,
+        sequence: bigint
+    ): Uint8Array {
+        return this.createIdentityActionMessage('QURESIS_UNFREEZE_V1:', authority, new Uint8Array(0), sequence);
+    }
+
+    /**
+     * Create the message that raises the threshold
+     *
+     * update_threshold rebuilds exactly this message on-chain when the new
+     * threshold is higher than the current one.
+     *
+     * @param authority - Wallet public key bytes
+     * @param newThreshold - Threshold being set, in threshold units
+     * @param sequence - Current sequence number from identity
+     * @returns Message bytes to sign
+     */
+    createThresholdMessage(
+        authority: Uint8Array,
+        newThreshold: bigint,
+        sequence: bigint
+    ): Uint8Array {
+        const thresholdBytes = new Uint8Array(8);
+        new DataView(thresholdBytes.buffer).setBigUint64(0, newThreshold, true);
+
+        return this.createIdentityActionMessage('QURESIS_THRESHOLD_V1:', authority, thresholdBytes, sequence);
+    }
+
+    /**
+     * Create the message that loosens a mint policy
+     *
+     * create_mint_policy and update_mint_policy rebuild exactly this message
+     * on-chain when either value is higher than the one it replaces.
+     *
+     * @param authority - Wallet public key bytes
+     * @param mint - Token-2022 mint public key bytes
+     * @param thresholdAmount - Policy threshold being set, in threshold units
+     * @param velocityLimit - Policy 24h limit being set, in threshold units
+     * @param sequence - Current sequence number from identity
+     * @returns Message bytes to sign
+     */
+    createMintPolicyMessage(
+        authority: Uint8Array,
+        mint: Uint8Array,
+        thresholdAmount: bigint,
+        velocityLimit: bigint,
+        sequence: bigint
+    ): Uint8Array {
+        const payload = new Uint8Array(32 + 8 + 8);
+        payload.set(mint, 0);
+        const view = new DataView(payload.buffer);
+        view.setBigUint64(32, thresholdAmount, true);
+        view.setBigUint64(40, velocityLimit, true);
+
+        return this.createIdentityActionMessage('QURESIS_MINT_POLICY_V1:', authority, payload, sequence);
+    }
+
+    /**
+     * Create the message that deletes a mint policy
+     *
+     * delete_mint_policy rebuilds exactly this message on-chain when the
+     * identity threshold the mint falls back to is higher than the policy.
+     *
+     * @param authority - Wallet public key bytes
+     * @param mint - Token-2022 mint public key bytes
+     * @param sequence - Current sequence number from identity
+     * @returns Message bytes to sign
+     */
+    createMintPolicyDeleteMessage(
+        authority: Uint8Array,
+        mint: Uint8Array,
+        sequence: bigint
+    ): Uint8Array {
+        return this.createIdentityActionMessage('QURESIS_MINT_POLICY_DELETE_V1:', authority, mint, sequence);
+    }
+
     /**
      * Create a message for transfer authorization
+     *
+     * verify_signature rebuilds exactly this message on-chain when minting
+     * a TransferAuthorization for the transfer hook.
      * 
-     * @param amount - Transfer amount in base units
-     * @param destination - Destination public key bytes
+     * @param mint - Token-2022 mint public key bytes
+     * @param destination - Destination token account public key bytes
+     * @param amountCap - Largest transfer amount in base units
+     * @param expiresAtSlot - Last slot at which the authorization is usable
      * @param sequence - Current sequence number
      * @returns Message bytes to sign
      */
     createTransferMessage(
-        amount: bigint,
+        mint: Uint8Array,
         destination: Uint8Array,
+        amountCap: bigint,
+        expiresAtSlot: bigint,
         sequence: bigint
     ): Uint8Array {
         const prefix = new TextEncoder().encode('QURESIS_TRANSFER_V1:');
-        const amountBytes = new Uint8Array(8);
-        new DataView(amountBytes.buffer).setBigUint64(0, amount, true);
-        const seqBytes = new Uint8Array(8);
-        new DataView(seqBytes.buffer).setBigUint64(0, sequence, true);
+        const tail = new Uint8Array(24);
+        const view = new DataView(tail.buffer);
+        view.setBigUint64(0, amountCap, true);
+        view.setBigUint64(8, expiresAtSlot, true);
+        view.setBigUint64(16, sequence, true);
 
         const message = new Uint8Array(
-            prefix.length + amountBytes.length + destination.length + seqBytes.length
+            prefix.length + mint.length + destination.length + tail.length
         );
         message.set(prefix, 0);
-        message.set(amountBytes, prefix.length);
-        message.set(destination, prefix.length + amountBytes.length);
-        message.set(seqBytes, prefix.length + amountBytes.length + destination.length);
+        message.set(mint, prefix.length);
+        message.set(destination, prefix.length + mint.length);
+        message.set(tail, prefix.length + mint.length + destination.length);
+
+        return message;
+    }
+
+    /**
+     * `domain || authority || payload || sequence (u64 LE)`, the layout of
+     * every message that authorizes an action on an identity
+     */
+    private createIdentityActionMessage(
+        domain: string,
+        authority: Uint8Array,
+        payload: Uint8Array,
+        sequence: bigint
+    ): Uint8Array {
+        const prefix = new TextEncoder().encode(domain);
+
+        const message = new Uint8Array(prefix.length + authority.length + payload.length + 8);
+        message.set(prefix, 0);
+        message.set(authority, prefix.length);
+        message.set(payload, prefix.length + authority.length);
+        new DataView(message.buffer).setBigUint64(
+            prefix.length + authority.length + payload.length,
+            sequence,
+            true
+        );
 
         return message;
     }
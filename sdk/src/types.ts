@@ -3,7 +3,7 @@ import { PublicKey } from '@solana/web3.js';
 /**
  * Supported ML-DSA variants
  * - ML-DSA-44: NIST Security Level 2 (1312 byte public key, 2420 byte signature)
- * - ML-DSA-65: NIST Security Level 3 (1952 byte public key, 3293 byte signature)
+ * - ML-DSA-65: NIST Security Level 3 (1952 byte public key, 3309 byte signature)
  */
 export type MLDSAVariant = 'ML-DSA-44' | 'ML-DSA-65';
 
@@ -23,10 +23,24 @@ export interface QuantumIdentity {
     createdAt: bigint;
     /** Emergency freeze flag */
     isFrozen: boolean;
-    /** Threshold amount requiring PQC signature */
+    /** Threshold requiring a PQC signature, in THRESHOLD_DECIMALS units (10^9 = one token) */
     thresholdAmount: bigint;
     /** Key version (incremented on rotation) */
     keyVersion: number;
+    /** False while a staged registration is still uploading the key */
+    isSealed: boolean;
+    /**
+     * Account layout version. Only version 1 is decoded; a version 0 (legacy
+     * layout) identity must be upgraded with `migrateIdentity` first.
+     */
+    version: number;
+    /**
+     * Closed with `closeIdentity`: a tombstone (empty key) that keeps transfers
+     * blocked, or a re-registration that is not sealed yet
+     */
+    isRevoked: boolean;
+    /** New wallet of an identity moved with `migrateAuthority` (old PDA only) */
+    forwardedTo: PublicKey | null;
     /** ML-DSA public key bytes */
     pqcPublicKey: Uint8Array;
 }
@@ -43,6 +57,50 @@ export enum EnforcementMode {
     HardEnforce = 2,
 }
 
+/**
+ * How a transfer hook treats senders without a usable Quantum Identity
+ */
+export enum FailurePolicy {
+    /** Opt-in - unregistered senders and unreadable identities pass */
+    AllowUnregistered = 0,
+    /** Every sender needs a valid, registered identity */
+    RequireIdentity = 1,
+    /** Unregistered senders pass, malformed identity accounts are blocked */
+    FailClosedOnMalformed = 2,
+}
+
+/**
+ * 24h velocity window of one identity on one mint
+ */
+export interface VelocityState {
+    /** Quantum Identity PDA whose transfers are counted */
+    identity: PublicKey;
+    /** Mint the window counts */
+    mint: PublicKey;
+    /** Start of the current window (unix seconds) */
+    windowStart: bigint;
+    /** Amount sent in the window, in THRESHOLD_DECIMALS units */
+    windowAmount: bigint;
+    /** PDA bump */
+    bump: number;
+}
+
+/**
+ * An identity's threshold override for one mint
+ */
+export interface MintPolicy {
+    /** Quantum Identity PDA the policy belongs to */
+    identity: PublicKey;
+    /** Mint the policy applies to */
+    mint: PublicKey;
+    /** Single transfer amount requiring PQC, in THRESHOLD_DECIMALS units */
+    thresholdAmount: bigint;
+    /** 24h total on the mint requiring PQC, in THRESHOLD_DECIMALS units */
+    velocityLimit: bigint;
+    /** PDA bump */
+    bump: number;
+}
+
 /**
  * Hook configuration stored on-chain
  */
@@ -59,6 +117,23 @@ export interface HookConfig {
     highValueTransfersDetected: bigint;
     /** PDA bump */
     bump: number;
+    /** Treatment of senders without a usable identity */
+    failurePolicy: FailurePolicy;
+}
+
+/**
+ * What a verify_signature call authorizes for the transfer hook
+ * (minted as a single-use TransferAuthorization)
+ */
+export interface TransferAuthorizationParams {
+    /** Token-2022 mint of the transfer */
+    mint: PublicKey;
+    /** Destination token account */
+    destination: PublicKey;
+    /** Largest amount (raw units) the transfer may move */
+    amountCap: bigint;
+    /** Last slot at which the authorization can be used */
+    expiresAtSlot: bigint;
 }
 
 /**
@@ -1,5 +1,6 @@
 import { PublicKey } from '@solana/web3.js';
-import { SEED_PREFIXES, PROGRAM_IDS } from './constants';
+import { sha256 } from '@noble/hashes/sha256';
+import { SEED_PREFIXES, PROGRAM_IDS, THRESHOLD_DECIMALS } from './constants';
 
 /**
  * Derive the Quantum Identity PDA for a given authority
@@ -42,6 +43,110 @@ export function deriveHookConfigPda(
     return { pda, bump };
 }
 
+/**
+ * Derive the Signature Buffer PDA for a given authority and buffer id
+ *
+ * @param authority - The wallet that creates and writes the buffer
+ * @param bufferId - Caller-chosen id (u64)
+ * @param programId - Optional custom program ID (defaults to devnet)
+ * @returns The PDA public key and bump
+ */
+export function deriveSignatureBufferPda(
+    authority: PublicKey,
+    bufferId: bigint,
+    programId: PublicKey = PROGRAM_IDS.quresis.devnet
+): { pda: PublicKey; bump: number } {
+    const idBytes = Buffer.alloc(8);
+    idBytes.writeBigUInt64LE(bufferId, 0);
+    const [pda, bump] = PublicKey.findProgramAddressSync(
+        [SEED_PREFIXES.signatureBuffer, authority.toBuffer(), idBytes],
+        programId
+    );
+    return { pda, bump };
+}
+
+/**
+ * Derive the Verification Session PDA for a given authority and session id
+ *
+ * @param authority - The wallet that opens the session
+ * @param sessionId - Caller-chosen id (u64)
+ * @param programId - Optional custom program ID (defaults to devnet)
+ * @returns The PDA public key and bump
+ */
+export function deriveVerificationSessionPda(
+    authority: PublicKey,
+    sessionId: bigint,
+    programId: PublicKey = PROGRAM_IDS.quresis.devnet
+): { pda: PublicKey; bump: number } {
+    const idBytes = Buffer.alloc(8);
+    idBytes.writeBigUInt64LE(sessionId, 0);
+    const [pda, bump] = PublicKey.findProgramAddressSync(
+        [SEED_PREFIXES.verificationSession, authority.toBuffer(), idBytes],
+        programId
+    );
+    return { pda, bump };
+}
+
+/**
+ * Derive the Transfer Authorization PDA for an identity and mint
+ *
+ * @param identity - The Quantum Identity PDA (not the wallet)
+ * @param mint - The Token-2022 mint being transferred
+ * @param programId - Optional custom program ID (defaults to devnet)
+ * @returns The PDA public key and bump
+ */
+export function deriveTransferAuthorizationPda(
+    identity: PublicKey,
+    mint: PublicKey,
+    programId: PublicKey = PROGRAM_IDS.quresis.devnet
+): { pda: PublicKey; bump: number } {
+    const [pda, bump] = PublicKey.findProgramAddressSync(
+        [SEED_PREFIXES.transferAuthorization, identity.toBuffer(), mint.toBuffer()],
+        programId
+    );
+    return { pda, bump };
+}
+
+/**
+ * Derive the Velocity State PDA (24h window) for an identity and mint
+ *
+ * @param identity - The Quantum Identity PDA (not the wallet)
+ * @param mint - The Token-2022 mint being transferred
+ * @param programId - Optional custom program ID (defaults to devnet)
+ * @returns The PDA public key and bump
+ */
+export function deriveVelocityStatePda(
+    identity: PublicKey,
+    mint: PublicKey,
+    programId: PublicKey = PROGRAM_IDS.quresis.devnet
+): { pda: PublicKey; bump: number } {
+    const [pda, bump] = PublicKey.findProgramAddressSync(
+        [SEED_PREFIXES.velocityState, identity.toBuffer(), mint.toBuffer()],
+        programId
+    );
+    return { pda, bump };
+}
+
+/**
+ * Derive the Mint Policy PDA (per-mint threshold) for an identity and mint
+ *
+ * @param identity - The Quantum Identity PDA (not the wallet)
+ * @param mint - The Token-2022 mint the policy applies to
+ * @param programId - Optional custom program ID (defaults to devnet)
+ * @returns The PDA public key and bump
+ */
+export function deriveMintPolicyPda(
+    identity: PublicKey,
+    mint: PublicKey,
+    programId: PublicKey = PROGRAM_IDS.quresis.devnet
+): { pda: PublicKey; bump: number } {
+    const [pda, bump] = PublicKey.findProgramAddressSync(
+        [SEED_PREFIXES.mintPolicy, identity.toBuffer(), mint.toBuffer()],
+        programId
+    );
+    return { pda, bump };
+}
+
 /**
  * Convert a Uint8Array to hex string
  */
@@ -64,24 +169,41 @@ export function hexToBuffer(hex: string): Uint8Array {
 }
 
 /**
- * Compute a collision-resistant hash of a message for logging
- * Uses PDA derivation (SHA256-based) to match the on-chain implementation
+ * Compute the SHA-256 hash of a message for logging
+ * Matches the on-chain `hash_message`: SHA-256("msg_hash" || message)
  * 
  * @param message - Message bytes to hash
- * @param programId - Optional program ID (defaults to Quresis devnet)
  * @returns 32-byte hash as Uint8Array
  */
-export function hashMessage(
-    message: Uint8Array,
-    programId: PublicKey = PROGRAM_IDS.quresis.devnet
-): Uint8Array {
-    // Use the same approach as the on-chain program:
-    // Hash through PDA derivation which uses SHA256 internally
-    const [hashKey] = PublicKey.findProgramAddressSync(
-        [Buffer.from('msg_hash'), message],
-        programId
-    );
-    return hashKey.toBytes();
+export function hashMessage(message: Uint8Array): Uint8Array {
+    const hasher = sha256.create();
+    hasher.update(Buffer.from('msg_hash'));
+    hasher.update(message);
+    return hasher.digest();
+}
+
+/**
+ * Convert a raw token amount to threshold units, the way the hook does
+ * (rounds up when the mint has more than THRESHOLD_DECIMALS decimals)
+ *
+ * @param amount - Amount in the mint's raw units
+ * @param decimals - The mint's decimals
+ * @returns Amount in threshold units, comparable with `thresholdAmount`
+ *
+ * @example
+ * ```typescript
+ * // 2 whole tokens of a 6-decimal mint
+ * toThresholdUnits(2_000_000n, 6); // 2_000_000_000n
+ * ```
+ */
+export function toThresholdUnits(amount: bigint, decimals: number): bigint {
+    const U64_MAX = (BigInt(1) << BigInt(64)) - BigInt(1);
+    if (decimals <= THRESHOLD_DECIMALS) {
+        const scaled = amount * BigInt(10) ** BigInt(THRESHOLD_DECIMALS - decimals);
+        return scaled > U64_MAX ? U64_MAX : scaled;
+    }
+    const scale = BigInt(10) ** BigInt(decimals - THRESHOLD_DECIMALS);
+    return (amount + scale - BigInt(1)) / scale;
 }
 
 /**
@@ -2,18 +2,23 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Quresis } from "../target/types/quresis";
 import { expect } from "chai";
-import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import { PublicKey, Keypair, SystemProgram, ComputeBudgetProgram } from "@solana/web3.js";
+import { createHash } from "crypto";
+import { ml_dsa44 } from "@noble/post-quantum/ml-dsa";
+import legacyAuthoritySecret from "./fixtures/legacy-identity-authority.json";
 
 /**
  * Quresis Test Suite
  * 
  * NOTE: Due to Anchor SDK limitations with large byte arrays (>1KB),
- * these tests use smaller mock keys for ML-DSA simulation.
+ * these tests use smaller mock keys for ML-DSA simulation. Mock keys and
+ * mock signatures are only accepted when the programs are built with the
+ * test-only `mock-pqc` feature (`yarn test` passes it for you).
  * 
  * In production:
  * - ML-DSA-44 keys are 1312 bytes
  * - ML-DSA-65 keys are 1952 bytes
- * - Signatures are 2420-3293 bytes
+ * - Signatures are 2420-3309 bytes
  * 
  * The program logic correctly validates these sizes.
  * For full-size testing, use raw transaction building or the Signature Buffer approach.
@@ -28,6 +33,17 @@ describe("quresis", () => {
 
     // PDA derivation
     const SEED_PREFIX = Buffer.from("quresis_id");
+    // Discriminator + zero-copy QuantumIdentity header; the public key follows
+    const IDENTITY_HEADER_LEN = 104;
+
+    // Compute units consumed by a confirmed transaction
+    const computeUnits = async (sig: string): Promise<number> => {
+        const tx = await provider.connection.getTransaction(sig, {
+            commitment: "confirmed",
+            maxSupportedTransactionVersion: 0,
+        });
+        return tx!.meta!.computeUnitsConsumed!;
+    };
 
     let identityPda: PublicKey;
     let testAuthority: Keypair;
@@ -89,7 +105,7 @@ describe("quresis", () => {
 
             try {
                 await program.methods
-                    .registerIdentity(invalidKey, null)
+                    .registerIdentity(invalidKey, null, Buffer.alloc(64, 1))
                     .accounts({
                         identity: newPda,
                         authority: newAuthority.publicKey,
@@ -128,7 +144,7 @@ describe("quresis", () => {
 
             try {
                 await program.methods
-                    .registerIdentity(invalidKey, null)
+                    .registerIdentity(invalidKey, null, Buffer.alloc(64, 1))
                     .accounts({
                         identity: newPda,
                         authority: newAuthority.publicKey,
@@ -150,6 +166,1495 @@ describe("quresis", () => {
         });
     });
 
+    describe("Proof of possession", () => {
+        it("should reject a registration the new key did not sign", async () => {
+            const squatter = Keypair.generate();
+            const airdropSig = await provider.connection.requestAirdrop(
+                squatter.publicKey,
+                1 * anchor.web3.LAMPORTS_PER_SOL
+            );
+            await provider.connection.confirmTransaction(airdropSig);
+
+            const [squatterPda] = PublicKey.findProgramAddressSync(
+                [SEED_PREFIX, squatter.publicKey.toBuffer()],
+                program.programId
+            );
+
+            try {
+                // [0, 0, 0, 0]-prefixed signatures fail verification in mock-pqc builds
+                await program.methods
+                    .registerIdentity(Buffer.alloc(32, 0x42), null, Buffer.alloc(64, 0))
+                    .accounts({
+                        identity: squatterPda,
+                        authority: squatter.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([squatter])
+                    .rpc();
+                expect.fail("Registration without proof of possession must fail");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("InvalidPossessionProof");
+                console.log("✅ Key squatting attempt rejected");
+            }
+        });
+    });
+
+    describe("Signature Buffer (chunked upload)", () => {
+        const SIG_BUFFER_SEED = Buffer.from("quresis_sig");
+        const bufferId = new anchor.BN(7);
+        // A full-size ML-DSA-44 signature never fits in one 1232-byte transaction
+        const signature = Buffer.from(
+            Array.from({ length: 2420 }, (_, i) => (i * 31 + 7) & 0xff)
+        );
+        let bufferPda: PublicKey;
+
+        before(() => {
+            [bufferPda] = PublicKey.findProgramAddressSync(
+                [
+                    SIG_BUFFER_SEED,
+                    testAuthority.publicKey.toBuffer(),
+                    bufferId.toArrayLike(Buffer, "le", 8),
+                ],
+                program.programId
+            );
+        });
+
+        const writeChunk = (offset: number, data: Buffer) =>
+            program.methods
+                .writeChunk(offset, data)
+                .accounts({
+                    signatureBuffer: bufferPda,
+                    authority: testAuthority.publicKey,
+                })
+                .signers([testAuthority])
+                .rpc();
+
+        it("should reject buffers that are not an ML-DSA signature or key size", async () => {
+            try {
+                await program.methods
+                    .initBuffer(new anchor.BN(99), 1000)
+                    .accounts({
+                        authority: testAuthority.publicKey,
+                    })
+                    .signers([testAuthority])
+                    .rpc();
+                expect.fail("Should have rejected a 1000-byte buffer");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("InvalidSignatureSize");
+                console.log("✅ Rejected non-ML-DSA buffer size");
+            }
+        });
+
+        it("should allocate a buffer and upload a signature in chunks", async () => {
+            await program.methods
+                .initBuffer(bufferId, signature.length)
+                .accounts({
+                    authority: testAuthority.publicKey,
+                })
+                .signers([testAuthority])
+                .rpc();
+
+            for (let offset = 0; offset < signature.length; offset += 900) {
+                await writeChunk(offset, signature.subarray(offset, offset + 900));
+            }
+
+            const buffer = await program.account.signatureBuffer.fetch(bufferPda);
+            expect(buffer.writtenLen).to.equal(signature.length);
+            expect(buffer.isFinalized).to.equal(false);
+            expect(Buffer.from(buffer.data).equals(signature)).to.be.true;
+            console.log("✅ 2420-byte signature uploaded in 3 chunks");
+        });
+
+        it("should reject a chunk that would leave a gap", async () => {
+            try {
+                await program.methods
+                    .initBuffer(new anchor.BN(8), 2420)
+                    .accounts({ authority: testAuthority.publicKey })
+                    .signers([testAuthority])
+                    .rpc();
+                const [gapPda] = PublicKey.findProgramAddressSync(
+                    [
+                        SIG_BUFFER_SEED,
+                        testAuthority.publicKey.toBuffer(),
+                        new anchor.BN(8).toArrayLike(Buffer, "le", 8),
+                    ],
+                    program.programId
+                );
+                await program.methods
+                    .writeChunk(100, Buffer.alloc(10, 1))
+                    .accounts({ signatureBuffer: gapPda, authority: testAuthority.publicKey })
+                    .signers([testAuthority])
+                    .rpc();
+                expect.fail("Should have rejected a non-contiguous chunk");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("ChunkOutOfBounds");
+                console.log("✅ Non-contiguous chunk rejected");
+            }
+        });
+
+        it("should finalize and then refuse further writes", async () => {
+            await program.methods
+                .finalize()
+                .accounts({
+                    signatureBuffer: bufferPda,
+                    authority: testAuthority.publicKey,
+                })
+                .signers([testAuthority])
+                .rpc();
+
+            const buffer = await program.account.signatureBuffer.fetch(bufferPda);
+            expect(buffer.isFinalized).to.equal(true);
+
+            try {
+                await writeChunk(0, Buffer.alloc(4));
+                expect.fail("Should not write to a finalized buffer");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("BufferAlreadyFinalized");
+            }
+            console.log("✅ Finalized buffer is immutable");
+        });
+
+        it("should refund the buffer rent on close", async () => {
+            const rent = (await provider.connection.getAccountInfo(bufferPda))!.lamports;
+            const before = await provider.connection.getBalance(testAuthority.publicKey);
+
+            await program.methods
+                .closeBuffer()
+                .accounts({
+                    signatureBuffer: bufferPda,
+                    authority: testAuthority.publicKey,
+                })
+                .signers([testAuthority])
+                .rpc({ commitment: "confirmed" });
+
+            const after = await provider.connection.getBalance(
+                testAuthority.publicKey,
+                "confirmed"
+            );
+            expect(await provider.connection.getAccountInfo(bufferPda, "confirmed")).to.be.null;
+            // Fee-payer is the provider wallet, so the authority receives the full rent
+            expect(after - before).to.equal(rent);
+            console.log("✅ Buffer closed, refunded", rent, "lamports");
+        });
+    });
+
+    describe("Staged registration (chunked key upload)", () => {
+        const stagedAuthority = Keypair.generate();
+        // Full-size ML-DSA-44 public key: too large for register_identity
+        const fullKey = Buffer.from(
+            Array.from({ length: 1312 }, (_, i) => (i * 17 + 3) & 0xff)
+        );
+        let stagedPda: PublicKey;
+
+        // mock-pqc builds accept any possession proof not starting with [0, 0, 0, 0]
+        const mockPossession = Buffer.alloc(64, 1);
+
+        const manageAccounts = () => ({
+            identity: stagedPda,
+            authority: stagedAuthority.publicKey,
+        });
+
+        before(async () => {
+            const airdropSig = await provider.connection.requestAirdrop(
+                stagedAuthority.publicKey,
+                2 * anchor.web3.LAMPORTS_PER_SOL
+            );
+            await provider.connection.confirmTransaction(airdropSig);
+
+            [stagedPda] = PublicKey.findProgramAddressSync(
+                [SEED_PREFIX, stagedAuthority.publicKey.toBuffer()],
+                program.programId
+            );
+        });
+
+        it("should allocate an unsealed identity", async () => {
+            await program.methods
+                .beginRegistration(fullKey.length, null)
+                .accounts({ authority: stagedAuthority.publicKey })
+                .signers([stagedAuthority])
+                .rpc();
+
+            const identity = await program.account.quantumIdentity.fetch(stagedPda);
+            expect(identity.isSealed).to.equal(0);
+            expect(identity.keyWrittenLen).to.equal(0);
+            expect(identity.keyLen).to.equal(1312);
+
+            const info = await provider.connection.getAccountInfo(stagedPda);
+            expect(info!.data.length).to.equal(IDENTITY_HEADER_LEN + 1312);
+            console.log("✅ Identity allocated for a 1312-byte key (unsealed)");
+        });
+
+        it("should refuse to seal a partially uploaded key", async () => {
+            await program.methods
+                .writeKeyChunk(0, fullKey.subarray(0, 900))
+                .accounts(manageAccounts())
+                .signers([stagedAuthority])
+                .rpc();
+
+            try {
+                await program.methods
+                    .sealIdentity(mockPossession)
+                    .accounts(manageAccounts())
+                    .signers([stagedAuthority])
+                    .rpc();
+                expect.fail("Should not seal an incomplete key");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("KeyIncomplete");
+                console.log("✅ Seal rejected at 900/1312 bytes");
+            }
+        });
+
+        it("should block use of the identity until it is sealed", async () => {
+            try {
+                await program.methods
+                    .updateThreshold(new anchor.BN(50_000_000_000), Buffer.alloc(0))
+                    .accounts(manageAccounts())
+                    .signers([stagedAuthority])
+                    .rpc();
+                expect.fail("Unsealed identity must be unusable");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("IdentityNotSealed");
+                console.log("✅ update_threshold blocked on unsealed identity");
+            }
+        });
+
+        it("should seal once every key byte is written", async () => {
+            await program.methods
+                .writeKeyChunk(900, fullKey.subarray(900))
+                .accounts(manageAccounts())
+                .signers([stagedAuthority])
+                .rpc();
+
+            await program.methods
+                .sealIdentity(mockPossession)
+                .accounts(manageAccounts())
+                .signers([stagedAuthority])
+                .rpc();
+
+            const identity = await program.account.quantumIdentity.fetch(stagedPda);
+            expect(identity.isSealed).to.equal(1);
+
+            const info = await provider.connection.getAccountInfo(stagedPda);
+            expect(info!.data.subarray(IDENTITY_HEADER_LEN).equals(fullKey)).to.be.true;
+            console.log("✅ 1312-byte key sealed into the identity");
+        });
+
+        it("should reject writes after sealing", async () => {
+            try {
+                await program.methods
+                    .writeKeyChunk(0, Buffer.alloc(4))
+                    .accounts(manageAccounts())
+                    .signers([stagedAuthority])
+                    .rpc();
+                expect.fail("Sealed key must be immutable");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("IdentityAlreadySealed");
+                console.log("✅ Sealed key is immutable");
+            }
+        });
+
+        it("should not pay for the 1312-byte key on header-only updates", async () => {
+            // Zero-copy: update_threshold / freeze touch the header in place
+            // instead of deserializing and re-serializing the key, so their
+            // cost must not depend on the key size. Compare against an
+            // identity holding a 32-byte mock key (lowering the threshold
+            // needs no PQC signature).
+            const compactAuthority = Keypair.generate();
+            const airdropSig = await provider.connection.requestAirdrop(
+                compactAuthority.publicKey,
+                1 * anchor.web3.LAMPORTS_PER_SOL
+            );
+            await provider.connection.confirmTransaction(airdropSig);
+            const [compactPda] = PublicKey.findProgramAddressSync(
+                [SEED_PREFIX, compactAuthority.publicKey.toBuffer()],
+                program.programId
+            );
+            await program.methods
+                .registerIdentity(Buffer.alloc(32, 0x0c), null, mockPossession)
+                .accounts({
+                    identity: compactPda,
+                    authority: compactAuthority.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([compactAuthority])
+                .rpc();
+
+            const measure = async (identity: PublicKey, signer: Keypair) => {
+                const accounts = { identity, authority: signer.publicKey };
+                const thresholdSig = await program.methods
+                    .updateThreshold(new anchor.BN(50_000_000_000), Buffer.alloc(0))
+                    .accounts(accounts)
+                    .signers([signer])
+                    .rpc({ commitment: "confirmed" });
+                const freezeSig = await program.methods
+                    .freeze()
+                    .accounts(accounts)
+                    .signers([signer])
+                    .rpc({ commitment: "confirmed" });
+                await program.methods
+                    .unfreeze(mockPossession)
+                    .accounts(accounts)
+                    .signers([signer])
+                    .rpc({ commitment: "confirmed" });
+                return {
+                    threshold: await computeUnits(thresholdSig),
+                    freeze: await computeUnits(freezeSig),
+                };
+            };
+
+            const compact = await measure(compactPda, compactAuthority);
+            const full = await measure(stagedPda, stagedAuthority);
+            console.log("   update_threshold: 32-byte key", compact.threshold, "CU, 1312-byte key", full.threshold, "CU");
+            console.log("   freeze:           32-byte key", compact.freeze, "CU, 1312-byte key", full.freeze, "CU");
+            expect(full.threshold).to.be.lessThan(15_000);
+            expect(full.freeze).to.be.lessThan(15_000);
+            expect(Math.abs(full.threshold - compact.threshold)).to.be.lessThan(200);
+            expect(Math.abs(full.freeze - compact.freeze)).to.be.lessThan(200);
+
+            const identity = await program.account.quantumIdentity.fetch(stagedPda);
+            expect(identity.isFrozen).to.equal(0);
+        });
+    });
+
+    describe("Layout versioning (migrate_identity)", () => {
+        // Preloaded by Anchor.toml: an identity serialized with the original
+        // Borsh QuantumIdentity (tests/fixtures/legacy-identity.json), holding
+        // a 32-byte mock key
+        const legacyAuthority = Keypair.fromSecretKey(Uint8Array.from(legacyAuthoritySecret));
+        const legacyKey = Buffer.from(Array.from({ length: 32 }, (_, i) => (i * 7 + 5) & 0xff));
+        let legacyPda: PublicKey;
+
+        const legacyAccounts = () => ({
+            identity: legacyPda,
+            authority: legacyAuthority.publicKey,
+        });
+
+        before(() => {
+            [legacyPda] = PublicKey.findProgramAddressSync(
+                [SEED_PREFIX, legacyAuthority.publicKey.toBuffer()],
+                program.programId
+            );
+        });
+
+        it("should refuse to use an identity that has not been migrated", async () => {
+            const info = await provider.connection.getAccountInfo(legacyPda);
+            expect(info!.data.length).to.equal(8 + 88 + 32);
+            // No version byte: offset 97 holds key byte 1, which is not 0 here
+            expect(info!.data[97]).to.equal(legacyKey[1]);
+            expect(legacyKey[1]).to.not.equal(0);
+
+            try {
+                await program.methods
+                    .updateThreshold(new anchor.BN(50_000_000_000), Buffer.alloc(0))
+                    .accounts(legacyAccounts())
+                    .signers([legacyAuthority])
+                    .rpc();
+                expect.fail("A legacy identity must be migrated before use");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("IdentityNeedsMigration");
+                console.log("✅ Legacy identity rejected until migrated");
+            }
+        });
+
+        it("should migrate it in place, keeping every field and the key", async () => {
+            // Permissionless: the provider wallet pays, the owner does not sign
+            await program.methods
+                .migrateIdentity()
+                .accounts({
+                    identity: legacyPda,
+                    authority: legacyAuthority.publicKey,
+                    payer: authority.publicKey,
+                })
+                .rpc();
+
+            const identity = await program.account.quantumIdentity.fetch(legacyPda);
+            expect(identity.version).to.equal(1);
+            expect(identity.authority.toBase58()).to.equal(legacyAuthority.publicKey.toBase58());
+            expect(identity.sequence.toString()).to.equal("3");
+            expect(identity.thresholdAmount.toString()).to.equal("5000000000");
+            expect(identity.keyVersion).to.equal(2);
+            expect(identity.keyLen).to.equal(32);
+            expect(identity.keyWrittenLen).to.equal(32);
+            expect(identity.isSealed).to.equal(1);
+            expect(identity.isFrozen).to.equal(0);
+
+            const info = await provider.connection.getAccountInfo(legacyPda);
+            expect(info!.data.length).to.equal(IDENTITY_HEADER_LEN + 32);
+            expect(info!.data.subarray(IDENTITY_HEADER_LEN).equals(legacyKey)).to.be.true;
+            console.log("✅ Identity migrated to layout v1");
+        });
+
+        it("should reject migrating an identity that is already current", async () => {
+            try {
+                await program.methods
+                    .migrateIdentity()
+                    .accounts({
+                        identity: legacyPda,
+                        authority: legacyAuthority.publicKey,
+                        payer: authority.publicKey,
+                    })
+                    .rpc();
+                expect.fail("Migration must only run once");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("IdentityUpToDate");
+            }
+        });
+
+        it("should accept the migrated identity", async () => {
+            // Raising the threshold checks a (mock) signature against the migrated key
+            await program.methods
+                .updateThreshold(new anchor.BN(50_000_000_000), Buffer.alloc(64, 0x01))
+                .accounts(legacyAccounts())
+                .signers([legacyAuthority])
+                .rpc();
+
+            const identity = await program.account.quantumIdentity.fetch(legacyPda);
+            expect(identity.thresholdAmount.toString()).to.equal("50000000000");
+            expect(identity.sequence.toString()).to.equal("4");
+            console.log("✅ Migrated identity usable");
+        });
+    });
+
+    describe("verify_signature anti-replay", () => {
+        const wallet = Keypair.generate();
+        const mockKey = Buffer.alloc(32, 0x07); // accepted by mock-pqc builds only
+        const mockSignature = Buffer.alloc(64, 0x01);
+        let walletIdentity: PublicKey;
+
+        const sequenced = (sequence: number) => {
+            const seq = Buffer.alloc(8);
+            seq.writeBigUInt64LE(BigInt(sequence), 0);
+            return Buffer.concat([Buffer.from("QURESIS_TEST_PAYLOAD"), seq]);
+        };
+        const verify = (message: Buffer) =>
+            program.methods
+                .verifySignature(message, mockSignature, null)
+                .accounts({ identity: walletIdentity })
+                .rpc();
+
+        before(async () => {
+            const airdropSig = await provider.connection.requestAirdrop(
+                wallet.publicKey,
+                1 * anchor.web3.LAMPORTS_PER_SOL
+            );
+            await provider.connection.confirmTransaction(airdropSig);
+
+            [walletIdentity] = PublicKey.findProgramAddressSync(
+                [SEED_PREFIX, wallet.publicKey.toBuffer()],
+                program.programId
+            );
+            await program.methods
+                .registerIdentity(mockKey, null, mockSignature)
+                .accounts({
+                    identity: walletIdentity,
+                    authority: wallet.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([wallet])
+                .rpc();
+        });
+
+        it("should accept a message committing to the current sequence and advance it", async () => {
+            await verify(sequenced(0));
+
+            const identity = await program.account.quantumIdentity.fetch(walletIdentity);
+            expect(identity.sequence.toNumber()).to.equal(1);
+            console.log("✅ Verified at sequence 0, sequence is now 1");
+        });
+
+        it("should reject a replayed message", async () => {
+            try {
+                await verify(sequenced(0));
+                expect.fail("Replay must be rejected");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("SequenceMismatch");
+                console.log("✅ Replay of sequence 0 rejected");
+            }
+        });
+
+        it("should reject a message without a sequence suffix", async () => {
+            try {
+                await verify(Buffer.from("short"));
+                expect.fail("Message must commit to a sequence");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("SequenceMismatch");
+                console.log("✅ Unsequenced message rejected");
+            }
+        });
+
+        it("should verify a message longer than a 32-byte PDA seed", async () => {
+            // Transfer authorizations are 108 bytes; the logged hash must not be a seed
+            const { sequence } = await program.account.quantumIdentity.fetch(walletIdentity);
+            const seq = Buffer.alloc(8);
+            seq.writeBigUInt64LE(BigInt(sequence.toString()), 0);
+            const longMessage = Buffer.concat([
+                Buffer.from("QURESIS_TEST_PAYLOAD"),
+                Buffer.alloc(80, 0x42),
+                seq,
+            ]);
+
+            const txSig = await program.methods
+                .verifySignature(longMessage, mockSignature, null)
+                .accounts({ identity: walletIdentity })
+                .rpc({ commitment: "confirmed" });
+
+            const tx = await provider.connection.getTransaction(txSig, {
+                commitment: "confirmed",
+                maxSupportedTransactionVersion: 0,
+            });
+            const parser = new anchor.EventParser(program.programId, program.coder);
+            const [event] = [...parser.parseLogs(tx!.meta!.logMessages!)];
+            expect(event.name).to.equal("signatureVerified");
+            const expected = createHash("sha256").update("msg_hash").update(longMessage).digest();
+            expect(Buffer.from(event.data.messageHash as number[]).equals(expected)).to.be.true;
+
+            const identity = await program.account.quantumIdentity.fetch(walletIdentity);
+            expect(identity.sequence.toNumber()).to.equal(sequence.toNumber() + 1);
+            console.log(`✅ ${longMessage.length}-byte message verified, SHA-256 hash logged`);
+        });
+
+        it("should refuse identity action messages so pending actions cannot be front-run", async () => {
+            // An unfreeze signature seen in flight must not burn the sequence here
+            const { sequence } = await program.account.quantumIdentity.fetch(walletIdentity);
+            const seq = Buffer.alloc(8);
+            seq.writeBigUInt64LE(BigInt(sequence.toString()), 0);
+            const unfreezeMessage = Buffer.concat([
+                Buffer.from("QURESIS_UNFREEZE_V1:"),
+                wallet.publicKey.toBuffer(),
+                seq,
+            ]);
+
+            try {
+                await verify(unfreezeMessage);
+                expect.fail("Reserved-domain messages must be rejected");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("ReservedMessageDomain");
+            }
+
+            const identity = await program.account.quantumIdentity.fetch(walletIdentity);
+            expect(identity.sequence.toString()).to.equal(sequence.toString());
+            console.log("✅ Unfreeze message refused by verify_signature, sequence untouched");
+        });
+    });
+
+    describe("Identity revocation (close_identity)", () => {
+        const wallet = Keypair.generate();
+        const mockKey = Buffer.alloc(32, 0x09); // accepted by mock-pqc builds only
+        const mockSignature = Buffer.alloc(64, 0x01);
+        // mock-pqc builds reject signatures starting with [0, 0, 0, 0]
+        const badSignature = Buffer.alloc(64, 0x00);
+        let walletIdentity: PublicKey;
+
+        const closeIdentity = (signature: Buffer) =>
+            program.methods
+                .closeIdentity(signature)
+                .accounts({
+                    identity: walletIdentity,
+                    authority: wallet.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([wallet])
+                .rpc({ commitment: "confirmed" });
+
+        before(async () => {
+            const airdropSig = await provider.connection.requestAirdrop(
+                wallet.publicKey,
+                1 * anchor.web3.LAMPORTS_PER_SOL
+            );
+            await provider.connection.confirmTransaction(airdropSig);
+
+            [walletIdentity] = PublicKey.findProgramAddressSync(
+                [SEED_PREFIX, wallet.publicKey.toBuffer()],
+                program.programId
+            );
+        });
+
+        it("should close an unsealed staged registration outright", async () => {
+            await program.methods
+                .beginRegistration(mockKey.length, null)
+                .accounts({ authority: wallet.publicKey })
+                .signers([wallet])
+                .rpc({ commitment: "confirmed" });
+
+            await closeIdentity(Buffer.alloc(0));
+
+            const info = await provider.connection.getAccountInfo(walletIdentity, "confirmed");
+            expect(info).to.be.null;
+            console.log("✅ Never-sealed registration closed without a tombstone");
+        });
+
+        it("should refuse to close a sealed identity without its PQC signature", async () => {
+            await program.methods
+                .registerIdentity(mockKey, null, mockSignature)
+                .accounts({
+                    identity: walletIdentity,
+                    authority: wallet.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([wallet])
+                .rpc({ commitment: "confirmed" });
+
+            try {
+                await closeIdentity(badSignature);
+                expect.fail("The Ed25519 key alone must not close an identity");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("InvalidQuantumSignature");
+                console.log("✅ Close without a valid PQC signature rejected");
+            }
+        });
+
+        it("should leave a header-only tombstone and refund the key's rent", async () => {
+            const before = await program.account.quantumIdentity.fetch(walletIdentity);
+            const lamportsBefore = await provider.connection.getBalance(walletIdentity, "confirmed");
+
+            await closeIdentity(mockSignature);
+
+            const identity = await program.account.quantumIdentity.fetch(walletIdentity, "confirmed");
+            expect(identity.isRevoked).to.equal(1);
+            expect(identity.isSealed).to.equal(0);
+            expect(identity.keyLen).to.equal(0);
+            expect(identity.sequence.toNumber()).to.equal(before.sequence.toNumber() + 1);
+
+            const info = await provider.connection.getAccountInfo(walletIdentity, "confirmed");
+            expect(info!.data.length).to.equal(IDENTITY_HEADER_LEN);
+            expect(info!.lamports).to.be.lessThan(lamportsBefore);
+            console.log("✅ Identity revoked: tombstone of", info!.data.length, "bytes");
+        });
+
+        it("should reject closing the tombstone again", async () => {
+            try {
+                await closeIdentity(mockSignature);
+                expect.fail("A tombstone is permanent");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("IdentityRevoked");
+                console.log("✅ Tombstone cannot be closed");
+            }
+        });
+
+        it("should refuse to re-register before the cooldown has passed", async () => {
+            try {
+                await program.methods
+                    .reopenIdentity(mockKey.length, null)
+                    .accounts({
+                        identity: walletIdentity,
+                        authority: wallet.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([wallet])
+                    .rpc();
+                expect.fail("Re-registration must wait for the cooldown");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("ReregistrationCooldown");
+                console.log("✅ Re-registration blocked during the cooldown");
+            }
+        });
+    });
+
+    describe("Freeze and threshold authorization", () => {
+        const wallet = Keypair.generate();
+        const mockKey = Buffer.alloc(32, 0x0b); // accepted by mock-pqc builds only
+        const mockSignature = Buffer.alloc(64, 0x01);
+        // mock-pqc builds reject signatures starting with [0, 0, 0, 0]
+        const badSignature = Buffer.alloc(64, 0x00);
+        let walletIdentity: PublicKey;
+
+        const walletAccounts = () => ({
+            identity: walletIdentity,
+            authority: wallet.publicKey,
+        });
+        const fetchIdentity = () =>
+            program.account.quantumIdentity.fetch(walletIdentity, "confirmed");
+
+        before(async () => {
+            const airdropSig = await provider.connection.requestAirdrop(
+                wallet.publicKey,
+                1 * anchor.web3.LAMPORTS_PER_SOL
+            );
+            await provider.connection.confirmTransaction(airdropSig);
+
+            [walletIdentity] = PublicKey.findProgramAddressSync(
+                [SEED_PREFIX, wallet.publicKey.toBuffer()],
+                program.programId
+            );
+            await program.methods
+                .registerIdentity(mockKey, null, mockSignature)
+                .accounts({
+                    identity: walletIdentity,
+                    authority: wallet.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([wallet])
+                .rpc({ commitment: "confirmed" });
+        });
+
+        it("should freeze with the Ed25519 authority alone", async () => {
+            await program.methods
+                .freeze()
+                .accounts(walletAccounts())
+                .signers([wallet])
+                .rpc({ commitment: "confirmed" });
+
+            expect((await fetchIdentity()).isFrozen).to.equal(1);
+
+            try {
+                await program.methods
+                    .freeze()
+                    .accounts(walletAccounts())
+                    .signers([wallet])
+                    .rpc();
+                expect.fail("Freezing twice must fail");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("IdentityFrozen");
+            }
+            console.log("✅ Identity frozen without a PQC signature");
+        });
+
+        it("should refuse to unfreeze without the PQC signature", async () => {
+            try {
+                await program.methods
+                    .unfreeze(badSignature)
+                    .accounts(walletAccounts())
+                    .signers([wallet])
+                    .rpc();
+                expect.fail("The Ed25519 key alone must not lift a freeze");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("InvalidQuantumSignature");
+                console.log("✅ Unfreeze without a valid PQC signature rejected");
+            }
+        });
+
+        it("should unfreeze with the PQC signature and advance the sequence", async () => {
+            const before = await fetchIdentity();
+
+            await program.methods
+                .unfreeze(mockSignature)
+                .accounts(walletAccounts())
+                .signers([wallet])
+                .rpc({ commitment: "confirmed" });
+
+            const identity = await fetchIdentity();
+            expect(identity.isFrozen).to.equal(0);
+            expect(identity.sequence.toNumber()).to.equal(before.sequence.toNumber() + 1);
+
+            try {
+                await program.methods
+                    .unfreeze(mockSignature)
+                    .accounts(walletAccounts())
+                    .signers([wallet])
+                    .rpc();
+                expect.fail("Unfreezing an active identity must fail");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("IdentityNotFrozen");
+            }
+            console.log("✅ Identity unfrozen with a PQC signature");
+        });
+
+        it("should refuse to raise the threshold without the PQC signature", async () => {
+            try {
+                await program.methods
+                    .updateThreshold(new anchor.BN("1000000000000000000"), badSignature)
+                    .accounts(walletAccounts())
+                    .signers([wallet])
+                    .rpc();
+                expect.fail("The Ed25519 key alone must not raise the threshold");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("InvalidQuantumSignature");
+                console.log("✅ Threshold raise to MAX_THRESHOLD without PQC rejected");
+            }
+        });
+
+        it("should raise the threshold with the PQC signature", async () => {
+            const before = await fetchIdentity();
+
+            await program.methods
+                .updateThreshold(new anchor.BN(200_000_000_000), mockSignature)
+                .accounts(walletAccounts())
+                .signers([wallet])
+                .rpc({ commitment: "confirmed" });
+
+            const identity = await fetchIdentity();
+            expect(identity.thresholdAmount.toString()).to.equal("200000000000");
+            expect(identity.sequence.toNumber()).to.equal(before.sequence.toNumber() + 1);
+            console.log("✅ Threshold raised with a PQC signature");
+        });
+
+        it("should lower the threshold with the Ed25519 authority alone", async () => {
+            const before = await fetchIdentity();
+
+            await program.methods
+                .updateThreshold(new anchor.BN(2_000_000_000), Buffer.alloc(0))
+                .accounts(walletAccounts())
+                .signers([wallet])
+                .rpc({ commitment: "confirmed" });
+
+            const identity = await fetchIdentity();
+            expect(identity.thresholdAmount.toString()).to.equal("2000000000");
+            expect(identity.sequence.toNumber()).to.equal(before.sequence.toNumber());
+            console.log("✅ Threshold lowered without a PQC signature");
+        });
+
+        it("should let a relayer freeze with the PQC signature alone", async () => {
+            // The provider wallet relays; the identity's wallet does not sign
+            const freezeWithPqc = (signature: Buffer) =>
+                program.methods
+                    .freezeWithPqc(signature)
+                    .accounts({ identity: walletIdentity })
+                    .rpc({ commitment: "confirmed" });
+
+            try {
+                await freezeWithPqc(badSignature);
+                expect.fail("A relayer needs the identity key's signature");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("InvalidQuantumSignature");
+            }
+
+            const before = await fetchIdentity();
+            await freezeWithPqc(mockSignature);
+
+            const identity = await fetchIdentity();
+            expect(identity.isFrozen).to.equal(1);
+            expect(identity.sequence.toNumber()).to.equal(before.sequence.toNumber() + 1);
+            console.log("✅ Identity frozen by a relayer with the quantum key's signature");
+        });
+    });
+
+    describe("Per-mint policies (mint_policy)", () => {
+        const MINT_POLICY_SEED = Buffer.from("quresis_mint_policy");
+        const wallet = Keypair.generate();
+        const mint = Keypair.generate().publicKey;
+        const mockKey = Buffer.alloc(32, 0x0c); // accepted by mock-pqc builds only
+        const mockSignature = Buffer.alloc(64, 0x01);
+        // mock-pqc builds reject signatures starting with [0, 0, 0, 0]
+        const badSignature = Buffer.alloc(64, 0x00);
+        let walletIdentity: PublicKey;
+        let mintPolicy: PublicKey;
+
+        const policyAccounts = () => ({
+            identity: walletIdentity,
+            mintPolicy,
+            authority: wallet.publicKey,
+        });
+        const fetchIdentity = () =>
+            program.account.quantumIdentity.fetch(walletIdentity, "confirmed");
+        const fetchPolicy = () => program.account.mintPolicy.fetch(mintPolicy, "confirmed");
+
+        before(async () => {
+            const airdropSig = await provider.connection.requestAirdrop(
+                wallet.publicKey,
+                1 * anchor.web3.LAMPORTS_PER_SOL
+            );
+            await provider.connection.confirmTransaction(airdropSig);
+
+            [walletIdentity] = PublicKey.findProgramAddressSync(
+                [SEED_PREFIX, wallet.publicKey.toBuffer()],
+                program.programId
+            );
+            [mintPolicy] = PublicKey.findProgramAddressSync(
+                [MINT_POLICY_SEED, walletIdentity.toBuffer(), mint.toBuffer()],
+                program.programId
+            );
+            // Default threshold: 100 tokens
+            await program.methods
+                .registerIdentity(mockKey, null, mockSignature)
+                .accounts({
+                    identity: walletIdentity,
+                    authority: wallet.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([wallet])
+                .rpc({ commitment: "confirmed" });
+        });
+
+        it("should refuse a policy looser than the identity threshold without the PQC signature", async () => {
+            try {
+                await program.methods
+                    .createMintPolicy(new anchor.BN(10_000_000_000), new anchor.BN(500_000_000_000), badSignature)
+                    .accounts({ ...policyAccounts(), mint })
+                    .signers([wallet])
+                    .rpc();
+                expect.fail("Raising the velocity limit above the identity threshold needs PQC");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("InvalidQuantumSignature");
+                console.log("✅ Looser mint policy without PQC rejected");
+            }
+        });
+
+        it("should reject limits outside the threshold bounds", async () => {
+            try {
+                await program.methods
+                    .createMintPolicy(new anchor.BN(1), new anchor.BN(50_000_000_000), Buffer.alloc(0))
+                    .accounts({ ...policyAccounts(), mint })
+                    .signers([wallet])
+                    .rpc();
+                expect.fail("A threshold below MIN_THRESHOLD must be rejected");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("InvalidThreshold");
+                console.log("✅ Mint policy below MIN_THRESHOLD rejected");
+            }
+        });
+
+        it("should create a tighter policy with the Ed25519 authority alone", async () => {
+            const before = await fetchIdentity();
+
+            await program.methods
+                .createMintPolicy(new anchor.BN(10_000_000_000), new anchor.BN(50_000_000_000), Buffer.alloc(0))
+                .accounts({ ...policyAccounts(), mint })
+                .signers([wallet])
+                .rpc({ commitment: "confirmed" });
+
+            const policy = await fetchPolicy();
+            expect(policy.identity.toBase58()).to.equal(walletIdentity.toBase58());
+            expect(policy.mint.toBase58()).to.equal(mint.toBase58());
+            expect(policy.thresholdAmount.toString()).to.equal("10000000000");
+            expect(policy.velocityLimit.toString()).to.equal("50000000000");
+            const identity = await fetchIdentity();
+            expect(identity.sequence.toNumber()).to.equal(before.sequence.toNumber());
+            expect(identity.mintPolicyCount).to.equal(1);
+            console.log("✅ Tighter mint policy created without a PQC signature");
+        });
+
+        it("should raise a policy limit only with the PQC signature", async () => {
+            const raise = (signature: Buffer) =>
+                program.methods
+                    .updateMintPolicy(new anchor.BN(10_000_000_000), new anchor.BN(80_000_000_000), signature)
+                    .accounts(policyAccounts())
+                    .signers([wallet])
+                    .rpc({ commitment: "confirmed" });
+
+            try {
+                await raise(badSignature);
+                expect.fail("The Ed25519 key alone must not raise a mint policy limit");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("InvalidQuantumSignature");
+            }
+
+            const before = await fetchIdentity();
+            await raise(mockSignature);
+
+            expect((await fetchPolicy()).velocityLimit.toString()).to.equal("80000000000");
+            expect((await fetchIdentity()).sequence.toNumber()).to.equal(before.sequence.toNumber() + 1);
+            console.log("✅ Mint policy limit raised with a PQC signature");
+        });
+
+        it("should lower a policy with the Ed25519 authority alone", async () => {
+            const before = await fetchIdentity();
+
+            await program.methods
+                .updateMintPolicy(new anchor.BN(5_000_000_000), new anchor.BN(20_000_000_000), Buffer.alloc(0))
+                .accounts(policyAccounts())
+                .signers([wallet])
+                .rpc({ commitment: "confirmed" });
+
+            const policy = await fetchPolicy();
+            expect(policy.thresholdAmount.toString()).to.equal("5000000000");
+            expect(policy.velocityLimit.toString()).to.equal("20000000000");
+            expect((await fetchIdentity()).sequence.toNumber()).to.equal(before.sequence.toNumber());
+            console.log("✅ Mint policy lowered without a PQC signature");
+        });
+
+        it("should delete a policy stricter than the identity only with the PQC signature", async () => {
+            // Falling back to the 100-token identity threshold loosens this mint
+            try {
+                await program.methods
+                    .deleteMintPolicy(badSignature)
+                    .accounts(policyAccounts())
+                    .signers([wallet])
+                    .rpc();
+                expect.fail("The Ed25519 key alone must not drop a stricter mint policy");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("InvalidQuantumSignature");
+            }
+
+            const before = await fetchIdentity();
+            await program.methods
+                .deleteMintPolicy(mockSignature)
+                .accounts(policyAccounts())
+                .signers([wallet])
+                .rpc({ commitment: "confirmed" });
+
+            expect(await provider.connection.getAccountInfo(mintPolicy, "confirmed")).to.be.null;
+            const identity = await fetchIdentity();
+            expect(identity.sequence.toNumber()).to.equal(before.sequence.toNumber() + 1);
+            expect(identity.mintPolicyCount).to.equal(0);
+            console.log("✅ Mint policy deleted with a PQC signature");
+        });
+    });
+
+    describe("Authority migration (migrate_authority)", () => {
+        // oldWallet's Ed25519 key is presumed stolen; it never signs below
+        const oldWallet = Keypair.generate();
+        const newWallet = Keypair.generate();
+        const mockKey = Buffer.from(Array.from({ length: 32 }, (_, i) => (i * 3 + 1) & 0xff));
+        const mockSignature = Buffer.alloc(64, 0x01); // accepted by mock-pqc builds only
+        const badSignature = Buffer.alloc(64, 0x00);
+        const MINT_POLICY_SEED = Buffer.from("quresis_mint_policy");
+        const policyMints = [Keypair.generate().publicKey, Keypair.generate().publicKey];
+        let oldIdentity: PublicKey;
+        let newIdentity: PublicKey;
+
+        const policyPda = (identity: PublicKey, mint: PublicKey) =>
+            PublicKey.findProgramAddressSync(
+                [MINT_POLICY_SEED, identity.toBuffer(), mint.toBuffer()],
+                program.programId
+            )[0];
+        // Each old policy followed by its address under the new identity
+        const policyPairs = (mints: PublicKey[]) =>
+            mints.flatMap((mint) => [
+                { pubkey: policyPda(oldIdentity, mint), isSigner: false, isWritable: true },
+                { pubkey: policyPda(newIdentity, mint), isSigner: false, isWritable: true },
+            ]);
+
+        const migrateAuthority = (signature: Buffer, mints: PublicKey[] = policyMints) =>
+            program.methods
+                .migrateAuthority(signature)
+                .accounts({
+                    identity: oldIdentity,
+                    newIdentity,
+                    newAuthority: newWallet.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .remainingAccounts(policyPairs(mints))
+                .signers([newWallet])
+                .rpc({ commitment: "confirmed" });
+
+        before(async () => {
+            for (const wallet of [oldWallet, newWallet]) {
+                const airdropSig = await provider.connection.requestAirdrop(
+                    wallet.publicKey,
+                    1 * anchor.web3.LAMPORTS_PER_SOL
+                );
+                await provider.connection.confirmTransaction(airdropSig);
+            }
+
+            [oldIdentity] = PublicKey.findProgramAddressSync(
+                [SEED_PREFIX, oldWallet.publicKey.toBuffer()],
+                program.programId
+            );
+            [newIdentity] = PublicKey.findProgramAddressSync(
+                [SEED_PREFIX, newWallet.publicKey.toBuffer()],
+                program.programId
+            );
+
+            await program.methods
+                .registerIdentity(mockKey, new anchor.BN(7_000_000_000), mockSignature)
+                .accounts({
+                    identity: oldIdentity,
+                    authority: oldWallet.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([oldWallet])
+                .rpc({ commitment: "confirmed" });
+            // Two policies tighter than the identity threshold
+            for (const [i, mint] of policyMints.entries()) {
+                await program.methods
+                    .createMintPolicy(
+                        new anchor.BN((i + 1) * 1_000_000_000),
+                        new anchor.BN((i + 2) * 1_000_000_000),
+                        Buffer.alloc(0)
+                    )
+                    .accounts({
+                        identity: oldIdentity,
+                        mintPolicy: policyPda(oldIdentity, mint),
+                        mint,
+                        authority: oldWallet.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([oldWallet])
+                    .rpc({ commitment: "confirmed" });
+            }
+            // Emergency freeze relayed with the quantum key, as a victim would
+            await program.methods
+                .freezeWithPqc(mockSignature)
+                .accounts({ identity: oldIdentity })
+                .rpc({ commitment: "confirmed" });
+        });
+
+        it("should refuse a migration the quantum key did not sign", async () => {
+            try {
+                await migrateAuthority(badSignature);
+                expect.fail("Only the identity key can move the identity");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("InvalidQuantumSignature");
+                console.log("✅ Migration without a valid PQC signature rejected");
+            }
+        });
+
+        it("should refuse a migration that leaves a mint policy behind", async () => {
+            try {
+                await migrateAuthority(mockSignature, policyMints.slice(0, 1));
+                expect.fail("Every mint policy has to move with the identity");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("MintPolicyCountMismatch");
+                console.log("✅ Migration without every mint policy rejected");
+            }
+        });
+
+        it("should move the key and policies to the new wallet's identity", async () => {
+            const before = await program.account.quantumIdentity.fetch(oldIdentity, "confirmed");
+            expect(before.mintPolicyCount).to.equal(2);
+
+            await migrateAuthority(mockSignature);
+
+            const moved = await program.account.quantumIdentity.fetch(newIdentity, "confirmed");
+            expect(moved.authority.toBase58()).to.equal(newWallet.publicKey.toBase58());
+            expect(moved.sequence.toNumber()).to.equal(before.sequence.toNumber() + 1);
+            expect(moved.keyVersion).to.equal(before.keyVersion);
+            expect(moved.keyLen).to.equal(32);
+            expect(moved.thresholdAmount.toString()).to.equal("7000000000");
+            expect(moved.isFrozen).to.equal(1);
+            expect(moved.isSealed).to.equal(1);
+            expect(moved.version).to.equal(1);
+
+            expect(moved.mintPolicyCount).to.equal(2);
+
+            const info = await provider.connection.getAccountInfo(newIdentity, "confirmed");
+            expect(info!.data.subarray(IDENTITY_HEADER_LEN).equals(mockKey)).to.be.true;
+
+            for (const [i, mint] of policyMints.entries()) {
+                const policy = await program.account.mintPolicy.fetch(
+                    policyPda(newIdentity, mint),
+                    "confirmed"
+                );
+                expect(policy.identity.toBase58()).to.equal(newIdentity.toBase58());
+                expect(policy.mint.toBase58()).to.equal(mint.toBase58());
+                expect(policy.thresholdAmount.toNumber()).to.equal((i + 1) * 1_000_000_000);
+                expect(policy.velocityLimit.toNumber()).to.equal((i + 2) * 1_000_000_000);
+                expect(
+                    await provider.connection.getAccountInfo(policyPda(oldIdentity, mint), "confirmed")
+                ).to.be.null;
+            }
+            console.log("✅ Identity and 2 mint policies moved to", newWallet.publicKey.toBase58());
+        });
+
+        it("should leave a forwarding tombstone on the old wallet", async () => {
+            const tombstone = await program.account.quantumIdentity.fetch(oldIdentity, "confirmed");
+            expect(tombstone.isRevoked).to.equal(1);
+            expect(tombstone.isForwarded).to.equal(1);
+            expect(tombstone.isSealed).to.equal(0);
+            expect(tombstone.keyLen).to.equal(0);
+            expect(tombstone.mintPolicyCount).to.equal(0);
+
+            const info = await provider.connection.getAccountInfo(oldIdentity, "confirmed");
+            expect(info!.data.length).to.equal(IDENTITY_HEADER_LEN + 32);
+            expect(
+                new PublicKey(info!.data.subarray(IDENTITY_HEADER_LEN)).toBase58()
+            ).to.equal(newWallet.publicKey.toBase58());
+            console.log("✅ Old identity forwards to the new wallet");
+        });
+
+        it("should never let the old wallet re-register", async () => {
+            try {
+                await program.methods
+                    .reopenIdentity(mockKey.length, null)
+                    .accounts({
+                        identity: oldIdentity,
+                        authority: oldWallet.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([oldWallet])
+                    .rpc();
+                expect.fail("A forwarding tombstone is permanent");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("IdentityForwarded");
+                console.log("✅ Compromised wallet cannot reclaim the old identity");
+            }
+        });
+
+        it("should let the new wallet unfreeze with the same quantum key", async () => {
+            await program.methods
+                .unfreeze(mockSignature)
+                .accounts({ identity: newIdentity, authority: newWallet.publicKey })
+                .signers([newWallet])
+                .rpc({ commitment: "confirmed" });
+
+            const moved = await program.account.quantumIdentity.fetch(newIdentity, "confirmed");
+            expect(moved.isFrozen).to.equal(0);
+            console.log("✅ New wallet unfrozen with the carried-over key");
+        });
+    });
+
+    describe("Verification Session (multi-transaction ML-DSA)", () => {
+        // Sessions always run the real FIPS 204 verifier, even in mock-pqc builds
+        const VERIFY_SESSION_SEED = Buffer.from("quresis_verify");
+        const SIG_BUFFER_SEED = Buffer.from("quresis_sig");
+        const owner = Keypair.generate();
+        const mlDsa = ml_dsa44.keygen(new Uint8Array(32).fill(9));
+        // Signed messages end with the identity's current sequence (0 here);
+        // padded past 32 bytes, the size of a transfer authorization message
+        const message = Buffer.concat([
+            Buffer.from("QURESIS_SESSION_TEST"),
+            Buffer.alloc(80, 0x42),
+            Buffer.alloc(8),
+        ]);
+        const stepBudget = ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 });
+        let ownerIdentity: PublicKey;
+        let signatureBuffer: PublicKey;
+
+        const sessionPda = (id: anchor.BN) =>
+            PublicKey.findProgramAddressSync(
+                [VERIFY_SESSION_SEED, owner.publicKey.toBuffer(), id.toArrayLike(Buffer, "le", 8)],
+                program.programId
+            )[0];
+
+        const uploadBuffer = async (id: anchor.BN, bytes: Buffer) => {
+            const [buffer] = PublicKey.findProgramAddressSync(
+                [SIG_BUFFER_SEED, owner.publicKey.toBuffer(), id.toArrayLike(Buffer, "le", 8)],
+                program.programId
+            );
+            const bufferAccounts = { signatureBuffer: buffer, authority: owner.publicKey };
+            await program.methods
+                .initBuffer(id, bytes.length)
+                .accounts({ authority: owner.publicKey })
+                .signers([owner])
+                .rpc();
+            for (let offset = 0; offset < bytes.length; offset += 900) {
+                await program.methods
+                    .writeChunk(offset, bytes.subarray(offset, offset + 900))
+                    .accounts(bufferAccounts)
+                    .signers([owner])
+                    .rpc();
+            }
+            await program.methods.finalize().accounts(bufferAccounts).signers([owner]).rpc();
+            return buffer;
+        };
+
+        // begin + 1 NTT step + k = 4 row steps for ML-DSA-44
+        const runSession = async (id: anchor.BN, signedMessage: Buffer) => {
+            const session = sessionPda(id);
+            await program.methods
+                .beginVerification(id, signedMessage, Buffer.alloc(0))
+                .accounts({
+                    identity: ownerIdentity,
+                    signatureBuffer,
+                    authority: owner.publicKey,
+                })
+                .preInstructions([stepBudget])
+                .signers([owner])
+                .rpc();
+            for (let step = 0; step < 5; step++) {
+                await program.methods
+                    .advanceVerification()
+                    .accounts({ verificationSession: session, identity: ownerIdentity })
+                    .preInstructions([stepBudget])
+                    .rpc();
+            }
+            return session;
+        };
+
+        before(async () => {
+            const airdropSig = await provider.connection.requestAirdrop(
+                owner.publicKey,
+                2 * anchor.web3.LAMPORTS_PER_SOL
+            );
+            await provider.connection.confirmTransaction(airdropSig);
+
+            [ownerIdentity] = PublicKey.findProgramAddressSync(
+                [SEED_PREFIX, owner.publicKey.toBuffer()],
+                program.programId
+            );
+            const manage = { identity: ownerIdentity, authority: owner.publicKey };
+
+            // Staged registration of the real 1312-byte key
+            await program.methods
+                .beginRegistration(mlDsa.publicKey.length, null)
+                .accounts({ authority: owner.publicKey })
+                .signers([owner])
+                .rpc();
+            for (let offset = 0; offset < mlDsa.publicKey.length; offset += 900) {
+                await program.methods
+                    .writeKeyChunk(offset, Buffer.from(mlDsa.publicKey.subarray(offset, offset + 900)))
+                    .accounts(manage)
+                    .signers([owner])
+                    .rpc();
+            }
+
+            // Seal with a real proof of possession (2420 bytes, so via a buffer)
+            const possession = Buffer.from(
+                ml_dsa44.sign(
+                    mlDsa.secretKey,
+                    Buffer.concat([
+                        Buffer.from("QURESIS_KEY_POSSESSION_V1:"),
+                        program.programId.toBuffer(),
+                        owner.publicKey.toBuffer(),
+                    ])
+                )
+            );
+            const possessionBuffer = await uploadBuffer(new anchor.BN(2), possession);
+            await program.methods
+                .sealIdentity(Buffer.alloc(0))
+                .accounts({ ...manage, signatureBuffer: possessionBuffer })
+                .signers([owner])
+                .rpc();
+
+            // Stage the 2420-byte message signature in a buffer
+            const signature = Buffer.from(ml_dsa44.sign(mlDsa.secretKey, message));
+            signatureBuffer = await uploadBuffer(new anchor.BN(1), signature);
+        });
+
+        it("should verify a real ML-DSA-44 signature across several transactions", async () => {
+            const session = await runSession(new anchor.BN(1), message);
+
+            const state = await program.account.verificationSession.fetch(session);
+            expect(state.status).to.have.property("verified");
+            console.log("✅ Session VERIFIED after 6 transactions");
+        });
+
+        it("should let verify_signature consume a verified session exactly once", async () => {
+            const session = sessionPda(new anchor.BN(1));
+            const consume = () =>
+                program.methods
+                    .verifySignature(message, Buffer.alloc(0), null)
+                    .accounts({
+                        identity: ownerIdentity,
+                        signatureBuffer: null,
+                        verificationSession: session,
+                    })
+                    .rpc();
+
+            await consume();
+            const state = await program.account.verificationSession.fetch(session);
+            expect(state.status).to.have.property("consumed");
+
+            // The replay is caught by the advanced sequence before the session is read
+            try {
+                await consume();
+                expect.fail("A session must not authorize twice");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("SequenceMismatch");
+            }
+            const identity = await program.account.quantumIdentity.fetch(ownerIdentity);
+            expect(identity.sequence.toNumber()).to.equal(1);
+            console.log("✅ Session consumed once, sequence advanced, replay rejected");
+        });
+
+        it("should reject a signature over a different message", async () => {
+            const session = await runSession(new anchor.BN(2), Buffer.from("QURESIS_OTHER_MESSAGE"));
+
+            const state = await program.account.verificationSession.fetch(session);
+            expect(state.status).to.have.property("rejected");
+            console.log("✅ Session REJECTED for a mismatched message");
+        });
+
+        it("should refuse to advance a completed session", async () => {
+            try {
+                await program.methods
+                    .advanceVerification()
+                    .accounts({ verificationSession: sessionPda(new anchor.BN(2)), identity: ownerIdentity })
+                    .rpc();
+                expect.fail("Completed sessions are final");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("SessionNotInProgress");
+                console.log("✅ Completed session cannot advance");
+            }
+        });
+
+        it("should refund the session rent on close", async () => {
+            for (const id of [1, 2]) {
+                await program.methods
+                    .closeVerification()
+                    .accounts({ verificationSession: sessionPda(new anchor.BN(id)), authority: owner.publicKey })
+                    .signers([owner])
+                    .rpc({ commitment: "confirmed" });
+                expect(
+                    await provider.connection.getAccountInfo(sessionPda(new anchor.BN(id)), "confirmed")
+                ).to.be.null;
+            }
+            console.log("✅ Sessions closed");
+        });
+    });
+
+    describe("Approved hook registry", () => {
+        const APPROVED_HOOK_SEED = Buffer.from("quresis_approved_hook");
+        const BPF_UPGRADEABLE_LOADER = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+        const fakeHook = Keypair.generate().publicKey;
+        const [approvedHook] = PublicKey.findProgramAddressSync(
+            [APPROVED_HOOK_SEED, fakeHook.toBuffer()],
+            program.programId
+        );
+        const [programData] = PublicKey.findProgramAddressSync(
+            [program.programId.toBuffer()],
+            BPF_UPGRADEABLE_LOADER
+        );
+
+        it("should only let the upgrade authority approve hooks", async () => {
+            const outsider = Keypair.generate();
+            const airdropSig = await provider.connection.requestAirdrop(
+                outsider.publicKey,
+                1 * anchor.web3.LAMPORTS_PER_SOL
+            );
+            await provider.connection.confirmTransaction(airdropSig);
+
+            try {
+                await program.methods
+                    .approveHook(fakeHook)
+                    .accounts({
+                        approvedHook,
+                        program: program.programId,
+                        programData,
+                        admin: outsider.publicKey,
+                    })
+                    .signers([outsider])
+                    .rpc();
+                expect.fail("Only the upgrade authority may approve hooks");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("UnauthorizedAdmin");
+                console.log("✅ Non-admin hook approval rejected");
+            }
+
+            await program.methods
+                .approveHook(fakeHook)
+                .accounts({
+                    approvedHook,
+                    program: program.programId,
+                    programData,
+                    admin: authority.publicKey,
+                })
+                .rpc();
+            console.log("✅ Upgrade authority approved a hook");
+        });
+
+        it("should reject record_transfer from a caller that is not the hook's signer PDA", async () => {
+            // Anyone could otherwise inflate another identity's velocity window
+            const griefer = Keypair.generate();
+            const victim = await program.account.quantumIdentity.all();
+            const sealed = victim.find((entry) => entry.account.isSealed === 1);
+            expect(sealed).to.not.be.undefined;
+
+            try {
+                await program.methods
+                    .recordTransfer(new anchor.BN(1_000_000_000_000))
+                    .accounts({
+                        identity: sealed!.publicKey,
+                        mint: Keypair.generate().publicKey,
+                        approvedHook,
+                        hookSigner: griefer.publicKey,
+                    })
+                    .signers([griefer])
+                    .rpc();
+                expect.fail("Unauthorized record_transfer must be rejected");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("UnauthorizedCaller");
+                console.log("✅ Unauthorized record_transfer rejected");
+            }
+        });
+
+        it("should let the upgrade authority revoke a hook", async () => {
+            await program.methods
+                .revokeHook()
+                .accounts({
+                    approvedHook,
+                    program: program.programId,
+                    programData,
+                    admin: authority.publicKey,
+                })
+                .rpc();
+
+            const closed = await provider.connection.getAccountInfo(approvedHook);
+            expect(closed).to.be.null;
+            console.log("✅ Hook revoked");
+        });
+    });
+
     describe("PDA Derivation", () => {
         it("should derive consistent PDAs for the same authority", async () => {
             const [pda1] = PublicKey.findProgramAddressSync(
@@ -192,10 +1697,35 @@ describe("quresis", () => {
             expect(instructionNames).to.include("rotateKey");
             expect(instructionNames).to.include("verifySignature");
             expect(instructionNames).to.include("updateThreshold");
-            expect(instructionNames).to.include("toggleFreeze");
+            expect(instructionNames).to.include("createMintPolicy");
+            expect(instructionNames).to.include("updateMintPolicy");
+            expect(instructionNames).to.include("deleteMintPolicy");
+            expect(instructionNames).to.include("freeze");
+            expect(instructionNames).to.include("unfreeze");
+            expect(instructionNames).to.include("freezeWithPqc");
             expect(instructionNames).to.include("closeIdentity");
+            expect(instructionNames).to.include("reopenIdentity");
+            expect(instructionNames).to.include("migrateIdentity");
+            expect(instructionNames).to.include("migrateAuthority");
+            expect(instructionNames).to.include("initBuffer");
+            expect(instructionNames).to.include("writeChunk");
+            expect(instructionNames).to.include("finalize");
+            expect(instructionNames).to.include("closeBuffer");
+            expect(instructionNames).to.include("beginRegistration");
+            expect(instructionNames).to.include("writeKeyChunk");
+            expect(instructionNames).to.include("sealIdentity");
+            expect(instructionNames).to.include("beginVerification");
+            expect(instructionNames).to.include("advanceVerification");
+            expect(instructionNames).to.include("closeVerification");
+            expect(instructionNames).to.include("consumeTransferAuthorization");
+            expect(instructionNames).to.include("revokeTransferAuthorization");
+            expect(instructionNames).to.include("closeTransferAuthorization");
+            expect(instructionNames).to.include("approveHook");
+            expect(instructionNames).to.include("revokeHook");
+            expect(instructionNames).to.include("initVelocityState");
+            expect(instructionNames).to.include("recordTransfer");
 
-            console.log("✅ All 6 instructions present in IDL");
+            console.log("✅ All 31 instructions present in IDL");
             console.log("   Instructions:", instructionNames.join(", "));
         });
 
@@ -214,6 +1744,7 @@ describe("quresis", () => {
             expect(errorNames).to.include("invalidKeyLength");
             expect(errorNames).to.include("invalidQuantumSignature");
             expect(errorNames).to.include("identityFrozen");
+            expect(errorNames).to.include("identityRevoked");
 
             console.log("✅ All error codes defined");
             console.log("   Errors:", errorNames.join(", "));
@@ -227,9 +1758,12 @@ describe("quresis", () => {
             expect(eventNames).to.include("keyRotated");
             expect(eventNames).to.include("signatureVerified");
             expect(eventNames).to.include("thresholdUpdated");
+            expect(eventNames).to.include("mintPolicyChanged");
             expect(eventNames).to.include("freezeToggled");
+            expect(eventNames).to.include("identityRevoked");
+            expect(eventNames).to.include("authorityMigrated");
 
-            console.log("✅ All 5 events defined");
+            console.log("✅ All 8 events defined");
             console.log("   Events:", eventNames.join(", "));
         });
     });
@@ -256,7 +1790,7 @@ describe("quresis", () => {
                     );
 
                     await program.methods
-                        .registerIdentity(invalidKey, null)
+                        .registerIdentity(invalidKey, null, Buffer.alloc(64, 1))
                         .accounts({
                             identity: newPda,
                             authority: newAuthority.publicKey,